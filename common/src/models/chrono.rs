@@ -39,6 +39,7 @@ impl<'v> rocket::form::FromFormField<'v> for DateTime {
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DateTimeFormat {
     YYYYMMDD,
+    YYYYMMDDHHMMSS,
 }
 
 impl fmt::Display for DateTimeFormat {
@@ -51,6 +52,7 @@ impl DateTimeFormat {
     pub fn as_str(&self) -> &'static str {
         match self {
             Self::YYYYMMDD => "%Y%m%d",
+            Self::YYYYMMDDHHMMSS => "%Y%m%d-%H%M%S",
         }
     }
 }
@@ -1,7 +1,8 @@
 use std::time::Duration;
 
 use anyhow::Result;
-use tokio::time::{sleep, Instant};
+use tokio::time::{sleep, timeout, Instant};
+use tokio_util::sync::CancellationToken;
 
 #[async_trait]
 pub trait AsyncWorker {
@@ -13,20 +14,36 @@ pub trait AsyncWorker {
 
     fn interval(&self) -> Duration;
 
+    /// 종료를 요청받은 후, 진행 중인 tick이 끝나기를 기다리는 최대 시간입니다.
+    ///
+    /// Kubernetes의 기본 유예 시간(30초)보다 짧아야 합니다.
+    fn shutdown_timeout(&self) -> Duration {
+        Duration::from_secs(20)
+    }
+
     async fn init(&self) -> Result<()> {
         Ok(())
     }
 
+    /// worker가 종료되기 전에 한 번 호출됩니다.
+    async fn shutdown(&self) -> Result<()> {
+        Ok(())
+    }
+
     async fn spawn_consuming_errors()
     where
         Self: Sized + Sync + 'static,
     {
         crate::init::init_logger();
+        let token = shutdown_on_signal();
         'main: loop {
-            match Self::spawn().await {
+            match Self::spawn_until(token.clone()).await {
                 Ok(()) => break 'main,
                 Err(error) => {
                     error!("fatal error: {err}\n- detail: {err:#?}", err = &error);
+                    if token.is_cancelled() {
+                        break 'main;
+                    }
                     tokio::time::sleep(Duration::from_secs(1)).await;
                 }
             }
@@ -34,44 +51,88 @@ pub trait AsyncWorker {
     }
 
     async fn spawn() -> Result<()>
+    where
+        Self: Sized + Sync + 'static,
+    {
+        Self::spawn_until(shutdown_on_signal()).await
+    }
+
+    async fn spawn_until(token: CancellationToken) -> Result<()>
     where
         Self: Sized + Sync + 'static,
     {
         match Self::try_new().await {
-            Ok(e) => e.spawn_inner().await,
+            Ok(e) => e.spawn_inner(token).await,
             Err(error) => panic!("fatal error: {err}\n- detail: {err:#?}", err = &error),
         }
     }
 
-    async fn spawn_inner(self) -> Result<()>
+    async fn spawn_inner(self, token: CancellationToken) -> Result<()>
     where
         Self: Sized + Sync + 'static,
     {
         self.init().await?;
         info!("worker inited");
-        tokio::spawn(self.loop_forever()).await?
+        tokio::spawn(self.loop_until_cancelled(token)).await?
     }
 
     async fn loop_forever(self) -> Result<()>
     where
         Self: Sized + Sync,
     {
-        loop {
-            self.schedule().await?;
+        self.loop_until_cancelled(shutdown_on_signal()).await
+    }
+
+    /// 토큰이 취소될 때까지 tick을 반복하고, `shutdown`을 호출합니다.
+    ///
+    /// 진행 중인 tick은 중단하지 않고 `shutdown_timeout`까지 기다리며, 기다리는 동안에는 즉시 종료합니다.
+    async fn loop_until_cancelled(self, token: CancellationToken) -> Result<()>
+    where
+        Self: Sized + Sync,
+    {
+        let result = self.schedule_until_cancelled(&token).await;
+        info!("worker shutting down");
+        if let Err(error) = self.shutdown().await {
+            error!(
+                "failed to shut down the worker: {err}\n- detail: {err:#?}",
+                err = &error,
+            );
         }
+        result
     }
 
-    async fn schedule(&self) -> Result<()> {
-        let time_begin = Instant::now();
-        let result = self.tick().await;
-        let time_end = Instant::now();
+    async fn schedule_until_cancelled(&self, token: &CancellationToken) -> Result<()> {
+        while !token.is_cancelled() {
+            let time_begin = Instant::now();
+            let mut tick = self.tick();
+            let result = tokio::select! {
+                result = &mut tick => result,
+                () = token.cancelled() => {
+                    info!("shutdown requested, waiting for the current tick to finish");
+                    let deadline = self.shutdown_timeout();
+                    return match timeout(deadline, tick).await {
+                        Ok(result) => result,
+                        Err(_) => Err(anyhow::anyhow!(
+                            "the current tick did not finish within {:?}",
+                            deadline,
+                        )),
+                    };
+                }
+            };
+            let time_end = Instant::now();
 
-        let interval = self.interval();
-        let elapsed = time_end - time_begin;
-        if interval > elapsed {
-            sleep(interval - elapsed).await;
+            // 실패한 경우에도 주기만큼 기다린 후 반환하여, 오류가 반복될 때 worker를 너무 자주 다시 만들지 않습니다.
+            let interval = self.interval();
+            let elapsed = time_end - time_begin;
+            if interval > elapsed {
+                tokio::select! {
+                    () = sleep(interval - elapsed) => {}
+                    () = token.cancelled() => {}
+                }
+            }
+            result?;
         }
-        result
+        Ok(())
     }
 
     async fn schedule_without_waiting(&self) -> Result<()> {
@@ -84,3 +145,38 @@ pub trait AsyncWorker {
         Ok(())
     }
 }
+
+/// 종료 신호(SIGTERM, Ctrl-C)를 받으면 취소되는 토큰을 만듭니다.
+pub fn shutdown_on_signal() -> CancellationToken {
+    let token = CancellationToken::new();
+    let child = token.clone();
+    tokio::spawn(async move {
+        wait_for_signal().await;
+        info!("received a shutdown signal");
+        child.cancel();
+    });
+    token
+}
+
+#[cfg(unix)]
+async fn wait_for_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    match signal(SignalKind::terminate()) {
+        Ok(mut terminate) => {
+            tokio::select! {
+                _ = terminate.recv() => {}
+                _ = tokio::signal::ctrl_c() => {}
+            }
+        }
+        Err(error) => {
+            warn!("failed to listen to SIGTERM: {}", error);
+            tokio::signal::ctrl_c().await.ok();
+        }
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_signal() {
+    tokio::signal::ctrl_c().await.ok();
+}
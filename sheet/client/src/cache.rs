@@ -0,0 +1,219 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use anyhow::Result;
+use smartx_whitedog_common::models::chrono::DateTime;
+
+use crate::Matrix;
+
+/// 문서를 읽은 결과입니다.
+#[derive(Clone, Debug)]
+pub struct Snapshot<T> {
+    /// 읽은 값
+    pub data: T,
+    /// 로컬 저장소의 값을 대신 반환한 경우, 해당 값이 저장된 시각
+    ///
+    /// `None`이라면 문서로부터 직접 읽은 최신 값입니다.
+    pub stale_since: Option<DateTime>,
+}
+
+impl<T> Snapshot<T> {
+    pub(crate) fn fresh(data: T) -> Self {
+        Self {
+            data,
+            stale_since: None,
+        }
+    }
+
+    /// 로컬 저장소의 값인지 여부를 반환합니다.
+    pub fn is_stale(&self) -> bool {
+        self.stale_since.is_some()
+    }
+
+    pub fn map<R>(self, f: impl FnOnce(T) -> R) -> Snapshot<R> {
+        Snapshot {
+            data: f(self.data),
+            stale_since: self.stale_since,
+        }
+    }
+}
+
+/// 문서의 마지막으로 성공한 읽기 결과와 아직 반영하지 못한 쓰기 요청을 보관하는 로컬 저장소입니다.
+///
+/// Google API에 접근할 수 없는 경우에도 worker가 동작할 수 있도록 합니다.
+/// * 읽기: 마지막으로 성공한 읽기 결과를 대신 반환합니다.
+/// * 쓰기: 요청을 대기열에 저장한 후, 연결이 복구되면 순서대로 다시 반영합니다.
+#[derive(Debug)]
+pub struct SnapshotCache {
+    root: PathBuf,
+    lock: Mutex<()>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Record {
+    /// 값이 위치한 범위
+    range: String,
+    data: Vec<Vec<String>>,
+    saved_at: DateTime,
+    /// 값을 읽을 때의 필드 구조
+    #[serde(default)]
+    layout: Option<String>,
+}
+
+impl SnapshotCache {
+    const PENDING: &'static str = "pending.json";
+
+    /// 주어진 디렉토리에 로컬 저장소를 생성합니다.
+    pub fn try_new(root: impl AsRef<Path>, spreadsheet_id: &str) -> Result<Self> {
+        let root = root.as_ref().join(spreadsheet_id);
+        fs::create_dir_all(&root)?;
+        Ok(Self {
+            root,
+            lock: Default::default(),
+        })
+    }
+
+    /// 주어진 범위를 읽은 결과를 저장합니다.
+    ///
+    /// `layout`은 값을 해석할 때 사용한 필드 구조로, 불러올 때 같은 구조인 경우에만 반환합니다.
+    pub(crate) fn save(&self, range: &str, matrix: &Matrix, layout: Option<&str>) -> Result<()> {
+        let record = Record {
+            range: matrix.shape.to_string(),
+            data: matrix.data.clone(),
+            saved_at: DateTime::now(),
+            layout: layout.map(ToString::to_string),
+        };
+
+        let _lock = self.lock.lock().unwrap();
+        self.write(&self.snapshot_path(range), &record)
+    }
+
+    pub(crate) fn load(
+        &self,
+        range: &str,
+        layout: Option<&str>,
+    ) -> Result<Option<Snapshot<Matrix>>> {
+        let _lock = self.lock.lock().unwrap();
+        let record: Record = match self.read(&self.snapshot_path(range))? {
+            Some(record) => record,
+            None => return Ok(None),
+        };
+        // 필드 구조가 바뀌었다면 열의 위치가 맞지 않으므로 사용하지 않습니다.
+        if record.layout.as_deref() != layout {
+            warn!(
+                "ignored the snapshot of {} saved with another layout",
+                range
+            );
+            return Ok(None);
+        }
+        Ok(Some(Snapshot {
+            data: Matrix {
+                shape: record.range.parse()?,
+                data: record.data,
+            },
+            stale_since: Some(record.saved_at),
+        }))
+    }
+
+    pub(crate) fn push_pending(&self, matrix: &Matrix) -> Result<()> {
+        let _lock = self.lock.lock().unwrap();
+        let path = self.root.join(Self::PENDING);
+
+        let mut pending: Vec<Record> = self.read(&path)?.unwrap_or_default();
+        pending.push(Record {
+            range: matrix.shape.to_string(),
+            data: matrix.data.clone(),
+            saved_at: DateTime::now(),
+            layout: None,
+        });
+        self.write(&path, &pending)
+    }
+
+    pub(crate) fn peek_pending(&self) -> Result<Option<Matrix>> {
+        let _lock = self.lock.lock().unwrap();
+        let pending: Vec<Record> = self
+            .read(&self.root.join(Self::PENDING))?
+            .unwrap_or_default();
+        pending
+            .into_iter()
+            .next()
+            .map(|record| {
+                Ok(Matrix {
+                    shape: record.range.parse()?,
+                    data: record.data,
+                })
+            })
+            .transpose()
+    }
+
+    pub(crate) fn pop_pending(&self) -> Result<()> {
+        let _lock = self.lock.lock().unwrap();
+        let path = self.root.join(Self::PENDING);
+
+        let mut pending: Vec<Record> = self.read(&path)?.unwrap_or_default();
+        if !pending.is_empty() {
+            pending.remove(0);
+        }
+        self.write(&path, &pending)
+    }
+
+    /// 아직 반영하지 못한 쓰기 요청의 수를 반환합니다.
+    pub fn num_pending(&self) -> Result<usize> {
+        let _lock = self.lock.lock().unwrap();
+        let pending: Vec<Record> = self
+            .read(&self.root.join(Self::PENDING))?
+            .unwrap_or_default();
+        Ok(pending.len())
+    }
+
+    fn snapshot_path(&self, range: &str) -> PathBuf {
+        let name: String = range.bytes().map(|b| format!("{:02x}", b)).collect();
+        self.root.join(format!("{}.json", name))
+    }
+
+    fn read<T>(&self, path: &Path) -> Result<Option<T>>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_reader(fs::File::open(path)?)?))
+    }
+
+    fn write<T>(&self, path: &Path, value: &T) -> Result<()>
+    where
+        T: serde::Serialize,
+    {
+        // 쓰는 도중에 프로그램이 종료되더라도 기존 파일이 손상되지 않도록 합니다.
+        let path_tmp = path.with_extension("tmp");
+        serde_json::to_writer(fs::File::create(&path_tmp)?, value)?;
+        fs::rename(path_tmp, path)?;
+        Ok(())
+    }
+}
+
+/// 연결 문제와 같이, 잠시 후 다시 시도하면 성공할 수 있는 오류인지 여부를 반환합니다.
+///
+/// 잘못된 요청이나 권한 부족과 같은 오류는 다시 시도하더라도 실패하므로, 대기열에 저장하지 않습니다.
+pub(crate) fn is_transient(error: &anyhow::Error) -> bool {
+    fn is_transient_status(code: u64) -> bool {
+        code == 429 || (500..600).contains(&code)
+    }
+
+    match error.downcast_ref::<google_sheets4::Error>() {
+        Some(google_sheets4::Error::HttpError(_))
+        | Some(google_sheets4::Error::Io(_))
+        | Some(google_sheets4::Error::MissingToken(_)) => true,
+        Some(google_sheets4::Error::BadRequest(response)) => response["error"]["code"]
+            .as_u64()
+            .map_or(false, is_transient_status),
+        Some(google_sheets4::Error::Failure(response)) => {
+            is_transient_status(response.status().as_u16().into())
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,201 @@
+use std::borrow::Cow;
+
+use anyhow::Result;
+use google_sheets4::api::{
+    BooleanCondition, ConditionValue, DataValidationRule, GridRange, Request,
+    SetDataValidationRequest,
+};
+use schemars::{
+    schema::{Schema, SchemaObject},
+    JsonSchema,
+};
+
+use crate::{parse_layout, resolve_schema, Protection, Spreadsheet, Table};
+
+/// 테이블의 열 설정입니다.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Column {
+    /// 구조체의 필드 이름
+    pub field: String,
+    /// 필드 행에 표시되는 이름 (기본값: snake_case로 변환하면 필드 이름과 같은 이름)
+    pub label: Option<String>,
+    /// 사람이 수정할 수 없는 열인지 여부
+    pub read_only: bool,
+    /// 선택할 수 있는 값 목록 (비어있다면 제한하지 않습니다)
+    pub choices: Vec<String>,
+}
+
+/// 하나의 구조체로 정의되는 테이블입니다.
+///
+/// 보통 `#[derive(SheetTable)]`으로 구현하며, `Spreadsheet::open_table`로 불러옵니다.
+pub trait SheetTable: JsonSchema {
+    /// 테이블 이름
+    const NAME: &'static str;
+    /// 필드 행 범위
+    const RANGE: &'static str;
+    /// 행을 구분하는 필드
+    const KEY: Option<&'static str> = None;
+
+    /// 기본값과 다르게 설정된 열 목록을 반환합니다.
+    fn columns() -> Vec<Column>;
+}
+
+/// 열거형 타입이 가질 수 있는 값 목록을 반환합니다.
+///
+/// 문자열로 직렬화되는 값만 포함하며, `Option`으로 감싼 타입도 지원합니다.
+pub fn enum_values<T>() -> Vec<String>
+where
+    T: JsonSchema,
+{
+    let root = schemars::schema_for!(T);
+    let definitions = root.definitions;
+    let schema = Schema::Object(root.schema);
+    resolve_schema(&schema, &definitions)
+        .map(|schema| schema_enum_values(schema, &definitions))
+        .unwrap_or_default()
+}
+
+/// 스키마에 나열된 문자열 값들을 반환합니다.
+///
+/// 각 값에 설명이 붙은 열거형은 `oneOf`로 표현되므로, 이를 함께 확인합니다.
+pub(crate) fn schema_enum_values(
+    schema: &SchemaObject,
+    definitions: &schemars::Map<String, Schema>,
+) -> Vec<String> {
+    let mut values: Vec<String> = schema
+        .enum_values
+        .iter()
+        .flatten()
+        .filter_map(|value| value.as_str().map(ToString::to_string))
+        .collect();
+    let one_of = schema
+        .subschemas
+        .as_ref()
+        .and_then(|subschemas| subschemas.one_of.as_ref());
+    for schema in one_of.into_iter().flatten() {
+        if let Some(schema) = resolve_schema(schema, definitions) {
+            values.extend(schema_enum_values(schema, definitions));
+        }
+    }
+    values
+}
+
+impl Spreadsheet {
+    /// 주어진 열 설정으로 테이블 객체를 불러옵니다.
+    ///
+    /// 읽기 전용 열은 보호 범위로, 선택할 수 있는 값이 주어진 열은 드롭다운으로 설정하며,
+    /// 각각 `sync_protections`와 `sync_choices`로 문서에 반영합니다.
+    pub async fn get_table_with<Field>(
+        &self,
+        fields_range: impl ToString,
+        columns: &[Column],
+    ) -> Result<Table<'_, Field>>
+    where
+        Field: JsonSchema,
+    {
+        let labels = columns
+            .iter()
+            .filter_map(|column| Some((column.label.clone()?, column.field.clone())))
+            .collect();
+        let layout = parse_layout::<Field>(self.get(&fields_range.to_string()).await?, &labels)?;
+        Ok(Table::from_layout(Cow::Borrowed(self), layout, labels).with_columns(columns))
+    }
+
+    /// 구조체로 정의된 테이블 객체를 불러옵니다.
+    pub async fn open_table<Field>(&self) -> Result<Table<'_, Field>>
+    where
+        Field: SheetTable,
+    {
+        self.get_table_with(Field::RANGE, &Field::columns()).await
+    }
+}
+
+impl<'a, Field> Table<'a, Field> {
+    fn with_columns(mut self, columns: &[Column]) -> Self {
+        let read_only: Vec<_> = columns
+            .iter()
+            .filter(|column| column.read_only)
+            .map(|column| column.field.clone())
+            .collect();
+        if !read_only.is_empty() {
+            self.protection = Some(Protection {
+                fields: read_only,
+                ..Default::default()
+            });
+        }
+
+        for column in columns.iter().filter(|column| !column.choices.is_empty()) {
+            self.choices
+                .insert(column.field.clone(), column.choices.clone());
+        }
+        self
+    }
+
+    /// 주어진 필드에서 선택할 수 있는 값 목록을 설정합니다.
+    ///
+    /// 설정한 목록은 `sync_choices`로 반영합니다.
+    pub fn with_choices(mut self, field: impl ToString, choices: Vec<String>) -> Self {
+        self.choices.insert(field.to_string(), choices);
+        self
+    }
+
+    /// 설정한 선택할 수 있는 값 목록을 현재 필드 구조에 맞게 드롭다운으로 반영합니다.
+    ///
+    /// 빈 값을 쓸 수 있도록, 목록에 없는 값을 막지 않고 경고만 표시합니다.
+    pub async fn sync_choices(&self) -> Result<()> {
+        if self.choices.is_empty() {
+            return Ok(());
+        }
+
+        let sheet_id = self.spreadsheet.sheet_id(&self.fields_shape.sheet).await?;
+        let requests = self
+            .choices
+            .iter()
+            .map(|(name, choices)| {
+                let field = self
+                    .fields
+                    .iter()
+                    .find(|e| &e.field == name)
+                    .ok_or_else(|| {
+                        anyhow!("no such field on \"{}\": {}", &self.fields_shape, name)
+                    })?;
+
+                let mut shape = self.values_shape(0, None);
+                shape.start.col += field.col;
+                shape.end.col = shape.start.col;
+                Ok(Request {
+                    set_data_validation: Some(SetDataValidationRequest {
+                        range: Some(GridRange {
+                            sheet_id: Some(sheet_id),
+                            start_row_index: shape.start.row.map(|row| row as i32),
+                            end_row_index: shape.end.row.map(|row| row as i32 + 1),
+                            start_column_index: Some(shape.start.col as i32),
+                            end_column_index: Some(shape.end.col as i32 + 1),
+                        }),
+                        rule: Some(DataValidationRule {
+                            condition: Some(BooleanCondition {
+                                type_: Some("ONE_OF_LIST".to_string()),
+                                values: Some(
+                                    choices
+                                        .iter()
+                                        .map(|choice| ConditionValue {
+                                            user_entered_value: Some(choice.clone()),
+                                            ..Default::default()
+                                        })
+                                        .collect(),
+                                ),
+                            }),
+                            strict: Some(false),
+                            show_custom_ui: Some(true),
+                            ..Default::default()
+                        }),
+                    }),
+                    ..Default::default()
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        self.spreadsheet.batch_update(requests).await?;
+        Ok(())
+    }
+}
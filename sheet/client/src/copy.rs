@@ -0,0 +1,133 @@
+use anyhow::Result;
+use google_sheets4::api::{
+    CopySheetToAnotherSpreadsheetRequest, DeleteSheetRequest, Request, SheetProperties,
+    UpdateSheetPropertiesRequest,
+};
+
+#[cfg(feature = "testing")]
+use crate::Backend;
+use crate::{Matrix, MatrixIndex, MatrixShape, Spreadsheet};
+
+impl Spreadsheet {
+    /// 문서의 모든 시트 이름을 불러옵니다.
+    pub async fn sheet_titles(&self) -> Result<Vec<String>> {
+        #[cfg(feature = "testing")]
+        if let Backend::Memory(sheets) = &self.backend {
+            return Ok(sheets.sheet_titles(&self.id));
+        }
+
+        let (_, ret) = self
+            .hub()?
+            .spreadsheets()
+            .get(&self.id)
+            .param("fields", "sheets.properties.title")
+            .doit()
+            .await?;
+        Ok(ret
+            .sheets
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|sheet| sheet.properties?.title)
+            .collect())
+    }
+
+    /// 주어진 시트를 다른 문서에 새로운 이름으로 복사합니다.
+    pub async fn copy_sheet_to(
+        &self,
+        title: &str,
+        destination: &Spreadsheet,
+        new_title: &str,
+    ) -> Result<()> {
+        let sheet_id = self.sheet_id(title).await?;
+        let request = CopySheetToAnotherSpreadsheetRequest {
+            destination_spreadsheet_id: Some(destination.id.clone()),
+        };
+        let (_, ret) = self
+            .hub()?
+            .spreadsheets()
+            .sheets_copy_to(request, &self.id, sheet_id)
+            .doit()
+            .await?;
+        let new_sheet_id = ret
+            .sheet_id
+            .ok_or_else(|| anyhow!("cannot copy the sheet: {}", title))?;
+
+        destination
+            .batch_update(vec![Request {
+                update_sheet_properties: Some(UpdateSheetPropertiesRequest {
+                    properties: Some(SheetProperties {
+                        sheet_id: Some(new_sheet_id),
+                        title: Some(new_title.to_string()),
+                        ..Default::default()
+                    }),
+                    fields: Some("title".to_string()),
+                }),
+                ..Default::default()
+            }])
+            .await?;
+        Ok(())
+    }
+
+    /// 주어진 시트를 삭제합니다.
+    pub async fn delete_sheet(&self, title: &str) -> Result<()> {
+        let sheet_id = self.sheet_id(title).await?;
+        self.batch_update(vec![Request {
+            delete_sheet: Some(DeleteSheetRequest {
+                sheet_id: Some(sheet_id),
+            }),
+            ..Default::default()
+        }])
+        .await?;
+        Ok(())
+    }
+
+    /// 다른 문서의 시트에 저장된 값과 수식으로 주어진 시트의 값을 덮어씁니다.
+    ///
+    /// 시트 자체를 교체하지 않으므로, 시트 ID와 행 식별 정보, 보호 범위 등은 유지됩니다.
+    /// 원본보다 넓은 영역에 남아있던 값은 빈 셀로 덮어씁니다.
+    pub async fn copy_values_from(
+        &self,
+        source: &Spreadsheet,
+        source_title: &str,
+        title: &str,
+    ) -> Result<()> {
+        let source = source.get_sheet_values(source_title).await?;
+        let current = self.get_sheet_values(title).await?;
+
+        let num_rows = source.len().max(current.len()).max(1);
+        let num_cols = source
+            .iter()
+            .chain(&current)
+            .map(Vec::len)
+            .max()
+            .unwrap_or_default()
+            .max(1);
+
+        let mut data = source;
+        data.resize_with(num_rows, Default::default);
+        for row in &mut data {
+            row.resize_with(num_cols, Default::default);
+        }
+
+        self.update_remote(Matrix {
+            shape: MatrixShape {
+                sheet: quote_title(title),
+                start: MatrixIndex::new(0, Some(0)),
+                end: MatrixIndex::new(num_cols as u16 - 1, Some(num_rows as u32 - 1)),
+            },
+            data,
+        })
+        .await
+    }
+
+    /// 시트 전체에 입력된 값을 수식 그대로 불러옵니다.
+    async fn get_sheet_values(&self, title: &str) -> Result<Vec<Vec<String>>> {
+        let mut values = self.get_entered_many(&[quote_title(title)]).await?;
+        Ok(values.pop().unwrap_or_default())
+    }
+}
+
+/// 범위 표기에 사용할 수 있도록 시트 이름을 따옴표로 감쌉니다.
+fn quote_title(title: &str) -> String {
+    format!("'{}'", title.trim_matches('\'').replace('\'', "''"))
+}
@@ -0,0 +1,57 @@
+use std::fmt;
+
+use anyhow::Result;
+
+/// 문서의 필드 행과 구조체의 필드 사이의 차이입니다.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SchemaDrift {
+    /// 구조체에 존재하지 않는 열의 이름
+    pub unknown: Vec<String>,
+    /// 문서에 존재하지 않는 생략 가능한 필드 (`None`으로 읽으며, 쓰지 않습니다)
+    pub missing: Vec<String>,
+    /// 2번 이상 등장한 필드 (첫번째 열만 사용합니다)
+    pub duplicated: Vec<String>,
+    /// 구조체에 선언된 순서와 다른 위치에 있는 필드
+    pub reordered: Vec<String>,
+}
+
+impl SchemaDrift {
+    /// 차이가 없는지 여부를 반환합니다.
+    pub fn is_empty(&self) -> bool {
+        self.unknown.is_empty()
+            && self.missing.is_empty()
+            && self.duplicated.is_empty()
+            && self.reordered.is_empty()
+    }
+
+    /// 엄격한 검사를 수행합니다.
+    ///
+    /// 필드 행에 알 수 없는 열이나 중복된 필드가 있거나, 생략 가능한 필드가 없다면 실패합니다.
+    pub fn ensure_strict(&self, range: impl fmt::Display) -> Result<()> {
+        if !self.unknown.is_empty() || !self.missing.is_empty() || !self.duplicated.is_empty() {
+            bail!("the fields on \"{}\" have been changed: {}", range, self);
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for SchemaDrift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reports = [
+            ("unknown", &self.unknown),
+            ("missing", &self.missing),
+            ("duplicated", &self.duplicated),
+            ("reordered", &self.reordered),
+        ];
+
+        let mut is_first = true;
+        for (label, fields) in reports.iter().filter(|(_, fields)| !fields.is_empty()) {
+            if !is_first {
+                "; ".fmt(f)?;
+            }
+            write!(f, "{}: {}", label, fields.join(", "))?;
+            is_first = false;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,104 @@
+use anyhow::Result;
+use schemars::schema::InstanceType;
+use serde_json::Value;
+
+use crate::{FieldName, Link};
+
+/// 참/거짓 값을 문서에 쓰는 방식입니다.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BoolFormat {
+    /// `Y` / `N`
+    YesNo,
+    /// `TRUE` / `FALSE` (체크박스와 같은 문서 고유의 참/거짓 값)
+    Native,
+}
+
+/// 셀의 값을 해석하고 쓰는 방식입니다.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CellFormat {
+    /// 참/거짓 값을 쓰는 방식
+    pub boolean: BoolFormat,
+    /// 빈 값을 쓸 때 사용하는 문자열
+    pub null: String,
+    /// 빈 값으로 해석하는 문자열 목록
+    pub null_tokens: Vec<String>,
+}
+
+impl Default for CellFormat {
+    fn default() -> Self {
+        Self {
+            boolean: BoolFormat::YesNo,
+            null: "N/A".to_string(),
+            null_tokens: vec![String::new(), "N/A".to_string()],
+        }
+    }
+}
+
+impl CellFormat {
+    /// 체크박스 셀을 위한 형식입니다.
+    ///
+    /// 참/거짓 값을 `TRUE` / `FALSE`로 쓰며, 빈 값은 빈 셀로 씁니다.
+    pub fn checkbox() -> Self {
+        Self {
+            boolean: BoolFormat::Native,
+            null: String::new(),
+            ..Default::default()
+        }
+    }
+
+    /// 빈 값을 빈 셀로 쓰는 형식입니다.
+    pub fn blank() -> Self {
+        Self {
+            null: String::new(),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn parse(&self, field: &FieldName, token: String) -> Result<Value> {
+        if self.null_tokens.iter().any(|null| null == &token) {
+            return Ok(Value::Null);
+        }
+
+        match field.ty {
+            InstanceType::Null => Ok(Value::Null),
+            InstanceType::Boolean => match token.trim().to_uppercase().as_str() {
+                "TRUE" | "YES" | "Y" | "O" | "V" => Ok(Value::Bool(true)),
+                "FALSE" | "NO" | "N" | "X" => Ok(Value::Bool(false)),
+                _ => bail!(
+                    "cannot parse the value into boolean \"{}\" ({})",
+                    token,
+                    &field.name
+                ),
+            },
+            InstanceType::Integer | InstanceType::Number => Ok(Value::Number(token.parse()?)),
+            // TODO: to be implemented
+            InstanceType::String => Ok(Value::String(token)),
+            InstanceType::Array => bail!("array fields are not supported: {}", &field.name),
+            InstanceType::Object if field.is_link() => Ok(serde_json::to_value(Link {
+                label: token,
+                url: None,
+            })?),
+            InstanceType::Object => unreachable!("Object type should be pruned"),
+        }
+    }
+
+    pub(crate) fn render(&self, field: &FieldName, value: Value) -> Result<String> {
+        match value {
+            Value::Null => Ok(self.null.clone()),
+            Value::Bool(value) => Ok(match (self.boolean, value) {
+                (BoolFormat::YesNo, true) => "Y",
+                (BoolFormat::YesNo, false) => "N",
+                (BoolFormat::Native, true) => "TRUE",
+                (BoolFormat::Native, false) => "FALSE",
+            }
+            .to_string()),
+            Value::Number(value) => Ok(value.to_string()),
+            Value::String(value) => Ok(value),
+            Value::Array(_) => bail!("array fields are not supported: {}", &field.name),
+            Value::Object(_) if field.is_link() => {
+                Ok(serde_json::from_value::<Link>(value)?.to_formula())
+            }
+            Value::Object(_) => unreachable!("Object type should be pruned"),
+        }
+    }
+}
@@ -1,24 +1,84 @@
 #[macro_use]
 extern crate anyhow;
-
-use std::{collections::BTreeMap, env, fmt, marker::PhantomData, str::FromStr};
+#[macro_use]
+extern crate log;
+#[macro_use]
+extern crate serde;
+
+mod cache;
+mod column;
+mod copy;
+mod drift;
+mod format;
+mod link;
+#[cfg(feature = "testing")]
+mod memory;
+mod protect;
+mod row;
+mod tag;
+mod transaction;
+
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, VecDeque},
+    env, fmt,
+    marker::PhantomData,
+    path::Path,
+    str::FromStr,
+    sync::Arc,
+};
 
 use anyhow::Result;
-use google_sheets4::{api::ValueRange, Sheets};
+use google_sheets4::{
+    api::{
+        BatchUpdateSpreadsheetRequest, BatchUpdateValuesRequest, ExtendedValue, GridData, Request,
+        Response, ValueRange,
+    },
+    Sheets,
+};
 use hyper_rustls::HttpsConnector;
 use inflector::Inflector;
 use schemars::{
-    schema::{InstanceType, ObjectValidation, Schema, SingleOrVec},
+    schema::{InstanceType, ObjectValidation, Schema, SchemaObject, SingleOrVec},
     JsonSchema,
 };
 use serde::{de::DeserializeOwned, Serialize};
 use serde_json::Value;
-use yup_oauth2::ServiceAccountAuthenticator;
+use yup_oauth2::{ServiceAccountAuthenticator, ServiceAccountKey};
+
+use self::{cache::is_transient, column::schema_enum_values};
+
+pub use self::{
+    cache::{Snapshot, SnapshotCache},
+    column::{enum_values, Column, SheetTable},
+    drift::SchemaDrift,
+    format::{BoolFormat, CellFormat},
+    link::Link,
+    protect::Protection,
+    row::Row,
+    tag::{RowTag, Tagged},
+    transaction::Transaction,
+};
+pub use smartx_whitedog_sheet_derive::SheetTable;
+
+#[cfg(feature = "testing")]
+pub use self::memory::MemorySheets;
 
 /// Google Sheets를 제어 가능한 클라이언트입니다.
 #[derive(Clone)]
 pub struct SheetClient {
-    hub: Sheets,
+    backend: Backend,
+    email: String,
+}
+
+/// 문서를 읽고 쓰는 대상입니다.
+#[derive(Clone)]
+enum Backend {
+    /// Google Sheets API
+    Remote(Sheets),
+    /// 메모리에 저장되는 문서 (테스트용)
+    #[cfg(feature = "testing")]
+    Memory(MemorySheets),
 }
 
 impl SheetClient {
@@ -37,7 +97,8 @@ impl SheetClient {
     pub async fn try_default() -> Result<Self> {
         // Get a service account info
         let path = env::var("GOOGLE_OAUTH2_SERVICE_ACCOUNT")?;
-        let key = serde_json::from_reader(std::fs::File::open(path)?)?;
+        let key: ServiceAccountKey = serde_json::from_reader(std::fs::File::open(path)?)?;
+        let email = key.client_email.clone();
 
         // Instantiate the authenticator. It will choose a suitable authentication flow for you,
         // unless you replace  `None` with the desired Flow.
@@ -52,156 +113,486 @@ impl SheetClient {
             hyper::Client::builder().build(HttpsConnector::with_native_roots()),
             auth,
         );
-        Ok(Self { hub })
+        Ok(Self {
+            backend: Backend::Remote(hub),
+            email,
+        })
+    }
+
+    /// 메모리에 저장되는 문서들을 사용하는 클라이언트를 만듭니다.
+    ///
+    /// Google API에 접근하지 않으므로, 인증 정보 없이 테스트에 사용할 수 있습니다.
+    #[cfg(feature = "testing")]
+    pub fn memory(sheets: MemorySheets) -> Self {
+        Self {
+            backend: Backend::Memory(sheets),
+            email: "memory@localhost".to_string(),
+        }
+    }
+
+    /// service account의 이메일 주소를 반환합니다.
+    pub fn email(&self) -> &str {
+        &self.email
     }
 
     pub fn into_sheet_unchecked(self, id: impl ToString) -> Spreadsheet {
         Spreadsheet {
-            client: self.hub,
+            backend: self.backend,
+            email: self.email,
             id: id.to_string(),
+            cache: None,
         }
     }
 }
 
 #[derive(Clone)]
 pub struct Spreadsheet {
-    client: Sheets,
+    backend: Backend,
+    email: String,
     id: String,
+    cache: Option<Arc<SnapshotCache>>,
 }
 
 impl Spreadsheet {
+    /// 로컬 저장소를 활용하도록 설정합니다.
+    ///
+    /// 연결 문제로 문서를 읽을 수 없는 경우 마지막으로 성공한 읽기 결과를 대신 반환하며,
+    /// 문서에 쓸 수 없는 경우 요청을 대기열에 저장한 후 연결이 복구되면 순서대로 다시 반영합니다.
+    /// 잘못된 요청이나 권한 부족과 같이 다시 시도하더라도 실패하는 오류는 그대로 반환합니다.
+    /// 단, 대기 중인 쓰기 요청은 로컬 저장소의 읽기 결과에 반영되지 않습니다.
+    /// 충돌을 확인하며 반영하는 `Table::set_rows_checked`는 로컬 저장소를 사용하지 않습니다.
+    pub fn with_cache(mut self, root: impl AsRef<Path>) -> Result<Self> {
+        self.cache = Some(Arc::new(SnapshotCache::try_new(root, &self.id)?));
+        Ok(self)
+    }
+
+    /// 대기 중인 쓰기 요청을 순서대로 반영하고, 반영한 요청의 수를 반환합니다.
+    ///
+    /// 다시 시도하더라도 실패하는 요청은 이후의 요청을 막지 않도록 오류를 기록한 후 버립니다.
+    pub async fn replay_pending(&self) -> Result<usize> {
+        let cache = match &self.cache {
+            Some(cache) => cache,
+            None => return Ok(0),
+        };
+
+        let mut count = 0;
+        while let Some(matrix) = cache.peek_pending()? {
+            let shape = matrix.shape.to_string();
+            match self.update_remote(matrix).await {
+                Ok(()) => count += 1,
+                Err(error) if is_transient(&error) => return Err(error),
+                Err(error) => error!("dropped the pending write to {}: {:#}", shape, error),
+            }
+            cache.pop_pending()?;
+        }
+        if count > 0 {
+            info!("replayed {} pending write(s) on {}", count, &self.id);
+        }
+        Ok(count)
+    }
+
     pub async fn get_table<Field>(&self, fields_range: impl ToString) -> Result<Table<'_, Field>>
     where
         Field: JsonSchema,
     {
-        fn parse_type(ty: InstanceType) -> Result<InstanceType> {
-            match ty {
-                InstanceType::Object => bail!("child struct is not supported"),
-                _ => Ok(ty),
-            }
+        let labels = Default::default();
+        let layout = parse_layout::<Field>(self.get(&fields_range.to_string()).await?, &labels)?;
+        Ok(Table::from_layout(Cow::Borrowed(self), layout, labels))
+    }
+
+    /// 문서를 소유하는 테이블 객체를 불러옵니다.
+    ///
+    /// 반환된 테이블은 `'static` 수명을 가지므로, worker 내부에 저장하거나 다른 task로 옮길 수 있습니다.
+    pub async fn into_table<Field>(self, fields_range: impl ToString) -> Result<OwnedTable<Field>>
+    where
+        Field: JsonSchema,
+    {
+        let labels = Default::default();
+        let layout = parse_layout::<Field>(self.get(&fields_range.to_string()).await?, &labels)?;
+        Ok(Table::from_layout(Cow::Owned(self), layout, labels))
+    }
+
+    async fn get(&self, range: &str) -> Result<Matrix> {
+        let snapshot = self.get_snapshot(range).await?;
+        if let Some(saved_at) = &snapshot.stale_since {
+            warn!(
+                "serving a stale snapshot of {} (saved at {:?})",
+                range, saved_at
+            );
         }
+        Ok(snapshot.data)
+    }
+
+    /// 로컬 저장소의 값을 사용하지 않고, 현재 문서의 값을 읽습니다.
+    ///
+    /// 대기 중인 쓰기 요청을 먼저 반영하며, 반영하지 못했다면 연결 오류를 그대로 반환합니다.
+    async fn get_fresh(&self, range: &str) -> Result<Matrix> {
+        self.replay_pending().await?;
+        self.get_remote(range).await
+    }
 
-        fn parse_schema(name: String, schema: Schema) -> Result<(String, InstanceType)> {
-            match schema {
-                Schema::Bool(_) => {
-                    bail!("a trivial boolean JSON Schema is not supported: {}", name)
+    async fn get_snapshot(&self, range: &str) -> Result<Snapshot<Matrix>> {
+        self.get_snapshot_with(range, None).await
+    }
+
+    /// 주어진 범위를 읽으며, 연결 문제로 읽을 수 없다면 같은 필드 구조로 저장된 읽기 결과를 대신 반환합니다.
+    async fn get_snapshot_with(
+        &self,
+        range: &str,
+        layout: Option<&str>,
+    ) -> Result<Snapshot<Matrix>> {
+        let cache = match &self.cache {
+            Some(cache) => cache,
+            None => return self.get_remote(range).await.map(Snapshot::fresh),
+        };
+
+        self.try_replay_pending(cache).await?;
+        match self.get_remote(range).await {
+            Ok(matrix) => {
+                if let Err(error) = cache.save(range, &matrix, layout) {
+                    warn!("failed to save the snapshot of {}: {}", range, error);
                 }
-                Schema::Object(schema) => {
-                    let ty = schema
-                        .instance_type
-                        .ok_or_else(|| anyhow!("cannot infer the type: {}", name))
-                        .and_then(|types| match types {
-                            SingleOrVec::Single(ty) => Ok(*ty),
-                            SingleOrVec::Vec(types) => {
-                                let mut types =
-                                    types.into_iter().filter(|ty| *ty != InstanceType::Null);
-                                let ty = types.next().unwrap_or(InstanceType::Null);
-                                match types.next() {
-                                    Some(_) => {
-                                        bail!(
-                                            "2 or more types in one type is not supported: {}",
-                                            name
-                                        )
-                                    }
-                                    None => Ok(ty),
-                                }
-                            }
-                        })
-                        .and_then(parse_type)?;
-                    Ok((name, ty))
+                Ok(Snapshot::fresh(matrix))
+            }
+            Err(error) if !is_transient(&error) => Err(error),
+            Err(error) => match cache.load(range, layout)? {
+                Some(snapshot) => {
+                    warn!("failed to read {}: {}", range, error);
+                    Ok(snapshot)
                 }
+                None => Err(error),
+            },
+        }
+    }
+
+    async fn get_many(&self, ranges: &[String]) -> Result<Vec<Matrix>> {
+        if ranges.is_empty() {
+            return Ok(vec![]);
+        }
+        let cache = match &self.cache {
+            Some(cache) => cache,
+            None => return self.get_many_remote(ranges).await,
+        };
+
+        self.try_replay_pending(cache).await?;
+        match self.get_many_remote(ranges).await {
+            Ok(matrices) => {
+                for (range, matrix) in ranges.iter().zip(&matrices) {
+                    if let Err(error) = cache.save(range, matrix, None) {
+                        warn!("failed to save the snapshot of {}: {}", range, error);
+                    }
+                }
+                Ok(matrices)
+            }
+            Err(error) if !is_transient(&error) => Err(error),
+            Err(error) => {
+                warn!("failed to read {} range(s): {}", ranges.len(), &error);
+                ranges
+                    .iter()
+                    .map(|range| match cache.load(range, None)? {
+                        Some(snapshot) => Ok(snapshot.data),
+                        None => bail!("failed to read {}: {}", range, &error),
+                    })
+                    .collect()
             }
         }
+    }
 
-        fn parse_object_properties(
-            object: Box<ObjectValidation>,
-        ) -> Result<Vec<(String, InstanceType)>> {
-            object
-                .properties
-                .into_iter()
-                .map(|(name, schema)| parse_schema(name, schema))
-                .collect()
-        }
-
-        fn parse_matrix(
-            struct_name: &str,
-            fields_struct: Vec<(String, InstanceType)>,
-            mut matrix: Matrix,
-        ) -> Result<Vec<FieldName>> {
-            let fields: Vec<_> = matrix
-                .cols()
-                .filter_map(|names| {
-                    names
-                        .into_iter()
-                        .map(|name| name.to_snake_case())
-                        .filter_map(|name| {
-                            fields_struct
-                                .iter()
-                                .enumerate()
-                                .find(|(_, (field, _))| &name == field)
-                                .map(|(index, (field, ty))| FieldName {
-                                    name,
-                                    field: field.clone(),
-                                    ty: *ty,
-                                })
-                        })
-                        .next()
+    async fn get_many_remote(&self, ranges: &[String]) -> Result<Vec<Matrix>> {
+        #[cfg(feature = "testing")]
+        if let Backend::Memory(sheets) = &self.backend {
+            return ranges
+                .iter()
+                .map(|range| sheets.read(&self.id, &range.parse()?))
+                .collect();
+        }
+
+        let mut call = self.hub()?.spreadsheets().values_batch_get(&self.id);
+        for range in ranges {
+            call = call.add_ranges(range);
+        }
+        let (_, ret) = call.doit().await?;
+
+        ret.value_ranges
+            .unwrap_or_default()
+            .into_iter()
+            .map(|ret| {
+                Ok(Matrix {
+                    shape: ret.range.expect("range").parse()?,
+                    data: ret.values.unwrap_or_default(),
                 })
+            })
+            .collect()
+    }
+
+    /// 주어진 범위들에 입력된 값을 수식 그대로 불러옵니다.
+    ///
+    /// 값 API와 같이 각 행의 끝에 있는 빈 셀과 끝에 있는 빈 행은 제외합니다.
+    /// 숫자나 참/거짓도 문자열로 불러오며, `USER_ENTERED`로 다시 쓰면 같은 값이 되도록
+    /// 문자열 값에는 `'`를 붙여 숫자나 수식으로 해석되지 않게 합니다.
+    pub(crate) async fn get_entered_many(
+        &self,
+        ranges: &[String],
+    ) -> Result<Vec<Vec<Vec<String>>>> {
+        #[cfg(feature = "testing")]
+        if let Backend::Memory(sheets) = &self.backend {
+            return ranges
+                .iter()
+                .map(|range| Ok(sheets.read(&self.id, &range.parse()?)?.data))
                 .collect();
+        }
+        if ranges.is_empty() {
+            return Ok(vec![]);
+        }
 
-            for (field_name, _) in fields_struct {
-                if let None = fields.iter().find(|field| &field.field == &field_name) {
-                    bail!(
-                        "cannot find the field \"{}\" for \"{}\" on \"{}\"",
-                        field_name,
-                        struct_name,
-                        &matrix.shape,
-                    );
+        // 값 API는 수식이 아닌 값을 JSON 숫자나 참/거짓으로 반환하므로, 셀에 입력된 값을 대신 읽습니다.
+        let mut request = self.hub()?.spreadsheets().get(&self.id);
+        for range in ranges {
+            request = request.add_ranges(range);
+        }
+        let (_, ret) = request
+            .include_grid_data(true)
+            .param(
+                "fields",
+                "sheets(properties.title,data.rowData.values.userEnteredValue)",
+            )
+            .doit()
+            .await?;
+
+        // 시트는 문서의 순서로 반환되며, 각 시트에서는 요청한 범위의 순서를 따릅니다.
+        let mut grids: BTreeMap<String, VecDeque<GridData>> = BTreeMap::new();
+        for sheet in ret.sheets.unwrap_or_default() {
+            let title = sheet
+                .properties
+                .and_then(|properties| properties.title)
+                .unwrap_or_default();
+            grids
+                .entry(title)
+                .or_default()
+                .extend(sheet.data.unwrap_or_default());
+        }
+        ranges
+            .iter()
+            .map(|range| {
+                let grid = grids
+                    .get_mut(&range_title(range))
+                    .and_then(VecDeque::pop_front)
+                    .ok_or_else(|| anyhow!("cannot read the range: {}", range))?;
+                let mut rows: Vec<Vec<String>> = grid
+                    .row_data
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|row| {
+                        let mut cols: Vec<_> = row
+                            .values
+                            .unwrap_or_default()
+                            .into_iter()
+                            .map(|cell| entered_value(cell.user_entered_value))
+                            .collect();
+                        while cols.last().map_or(false, String::is_empty) {
+                            cols.pop();
+                        }
+                        cols
+                    })
+                    .collect();
+                while rows.last().map_or(false, Vec::is_empty) {
+                    rows.pop();
                 }
+                Ok(rows)
+            })
+            .collect()
+    }
+
+    async fn try_replay_pending(&self, cache: &SnapshotCache) -> Result<()> {
+        if cache.num_pending()? > 0 {
+            if let Err(error) = self.replay_pending().await {
+                warn!("failed to replay pending writes: {}", error);
             }
-            Ok(fields)
-        }
-
-        let mut schema = schemars::schema_for!(Field).schema;
-        let name = schema
-            .metadata()
-            .title
-            .clone()
-            .unwrap_or_else(|| "unknown field".to_string());
-        match schema.object {
-            Some(object) => {
-                let fields_range = fields_range.to_string();
-                let fields_struct = parse_object_properties(object)?;
-                let fields_matrix = self.get(&fields_range).await?;
-
-                Ok(Table {
-                    spreadsheet: self,
-                    fields_shape: fields_matrix.shape.clone(),
-                    fields: parse_matrix(&name, fields_struct, fields_matrix)?,
-                    _table: PhantomData::<Field>::default(),
-                })
-            }
-            None => bail!(
-                "field {} is not a struct (not supported: enum, union, ...)",
-                name
-            ),
         }
+        Ok(())
     }
 
-    async fn get(&self, range: &str) -> Result<Matrix> {
+    async fn get_remote(&self, range: &str) -> Result<Matrix> {
+        #[cfg(feature = "testing")]
+        if let Backend::Memory(sheets) = &self.backend {
+            return sheets.read(&self.id, &range.parse()?);
+        }
+
         let (_, ret) = self
-            .client
+            .hub()?
             .spreadsheets()
             .values_get(&self.id, range)
             .doit()
             .await?;
         Ok(Matrix {
             shape: ret.range.expect("range").parse()?,
-            data: ret.values.expect("values"),
+            data: ret.values.unwrap_or_default(),
         })
     }
 
-    async fn update(&self, matrix: Matrix) -> Result<()> {
+    /// 시트 이름에 해당하는 시트 ID를 불러옵니다.
+    pub async fn sheet_id(&self, title: &str) -> Result<i32> {
+        let title = title.trim_matches('\'');
+        #[cfg(feature = "testing")]
+        if let Backend::Memory(sheets) = &self.backend {
+            return sheets
+                .sheet_id(&self.id, title)
+                .ok_or_else(|| anyhow!("cannot find the sheet: {}", title));
+        }
+
+        let (_, ret) = self
+            .hub()?
+            .spreadsheets()
+            .get(&self.id)
+            .param("fields", "sheets.properties(sheetId,title)")
+            .doit()
+            .await?;
+        ret.sheets
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|sheet| sheet.properties)
+            .find(|properties| properties.title.as_deref() == Some(title))
+            .and_then(|properties| properties.sheet_id)
+            .ok_or_else(|| anyhow!("cannot find the sheet: {}", title))
+    }
+
+    fn hub(&self) -> Result<&Sheets> {
+        match &self.backend {
+            Backend::Remote(hub) => Ok(hub),
+            #[cfg(feature = "testing")]
+            Backend::Memory(_) => bail!("not supported on the in-memory spreadsheet: {}", &self.id),
+        }
+    }
+
+    async fn batch_update(&self, requests: Vec<Request>) -> Result<Vec<Response>> {
+        if requests.is_empty() {
+            return Ok(vec![]);
+        }
+        // 메모리에 저장되는 문서는 서식이나 보호 범위 등을 반영하지 않고, 요청만 기록합니다.
+        #[cfg(feature = "testing")]
+        if let Backend::Memory(sheets) = &self.backend {
+            sheets.record(&self.id, requests)?;
+            return Ok(vec![]);
+        }
+
+        let request = BatchUpdateSpreadsheetRequest {
+            requests: Some(requests),
+            ..Default::default()
+        };
+        let (_, ret) = self
+            .hub()?
+            .spreadsheets()
+            .batch_update(request, &self.id)
+            .doit()
+            .await?;
+        Ok(ret.replies.unwrap_or_default())
+    }
+
+    /// 주어진 범위의 표 아래에 행들을 추가합니다.
+    ///
+    /// 값은 입력한 그대로 저장되며, 수식으로 해석되지 않습니다.
+    pub async fn append(&self, range: &str, data: Vec<Vec<String>>) -> Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        #[cfg(feature = "testing")]
+        if let Backend::Memory(sheets) = &self.backend {
+            return sheets.append(&self.id, range, data);
+        }
+
+        let value_range = ValueRange {
+            major_dimension: None,
+            range: Some(range.to_string()),
+            values: Some(data),
+        };
+        self.hub()?
+            .spreadsheets()
+            .values_append(value_range, &self.id, range)
+            .value_input_option("RAW")
+            .insert_data_option("INSERT_ROWS")
+            .doit()
+            .await?;
+        Ok(())
+    }
+
+    /// 행렬들을 반영합니다.
+    ///
+    /// 연결 문제로 반영할 수 없다면 요청을 대기열에 저장하고 `WriteStatus::Queued`를 반환하며,
+    /// 그 외의 오류는 그대로 반환합니다.
+    async fn update_many(&self, matrices: Vec<Matrix>) -> Result<WriteStatus> {
+        let cache = match &self.cache {
+            Some(cache) => cache,
+            None => {
+                self.update_many_remote(matrices).await?;
+                return Ok(WriteStatus::Written);
+            }
+        };
+
+        // 쓰기 요청의 순서를 보장하기 위해, 대기 중인 요청을 먼저 반영합니다.
+        let result = match self.replay_pending().await {
+            Ok(_) => self.update_many_remote(matrices.clone()).await,
+            Err(error) => Err(error),
+        };
+        match result {
+            Ok(()) => Ok(WriteStatus::Written),
+            Err(error) if is_transient(&error) => {
+                for matrix in &matrices {
+                    warn!(
+                        "failed to write {}, queued for replay: {}",
+                        &matrix.shape, error
+                    );
+                    cache.push_pending(matrix)?;
+                }
+                Ok(WriteStatus::Queued)
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    async fn update_many_remote(&self, mut matrices: Vec<Matrix>) -> Result<()> {
+        #[cfg(feature = "testing")]
+        if let Backend::Memory(sheets) = &self.backend {
+            for matrix in matrices {
+                sheets.write(&self.id, matrix)?;
+            }
+            return Ok(());
+        }
+
+        if matrices.len() <= 1 {
+            return match matrices.pop() {
+                Some(matrix) => self.update_remote(matrix).await,
+                None => Ok(()),
+            };
+        }
+
+        let request = BatchUpdateValuesRequest {
+            data: Some(
+                matrices
+                    .into_iter()
+                    .map(|matrix| ValueRange {
+                        major_dimension: None,
+                        range: Some(matrix.shape.to_string()),
+                        values: Some(matrix.data),
+                    })
+                    .collect(),
+            ),
+            value_input_option: Some("USER_ENTERED".to_string()),
+            ..Default::default()
+        };
+        self.hub()?
+            .spreadsheets()
+            .values_batch_update(request, &self.id)
+            .doit()
+            .await?;
+        Ok(())
+    }
+
+    async fn update_remote(&self, matrix: Matrix) -> Result<()> {
+        #[cfg(feature = "testing")]
+        if let Backend::Memory(sheets) = &self.backend {
+            return sheets.write(&self.id, matrix);
+        }
+
         let range = matrix.shape.to_string();
         let value_range = ValueRange {
             major_dimension: None,
@@ -209,7 +600,7 @@ impl Spreadsheet {
             values: Some(matrix.data),
         };
 
-        self.client
+        self.hub()?
             .spreadsheets()
             .values_update(value_range, &self.id, &range)
             .value_input_option("USER_ENTERED")
@@ -221,126 +612,570 @@ impl Spreadsheet {
 
 #[derive(Clone)]
 pub struct Table<'a, Field> {
-    spreadsheet: &'a Spreadsheet,
+    spreadsheet: Cow<'a, Spreadsheet>,
     fields: Vec<FieldName>,
     fields_shape: MatrixShape,
+    /// 필드 행에 표시되는 이름 -> 필드 이름
+    labels: BTreeMap<String, String>,
+    format: CellFormat,
+    formats: BTreeMap<String, CellFormat>,
+    drift: SchemaDrift,
+    strict: bool,
+    protection: Option<Protection>,
+    /// 필드별 선택할 수 있는 값 목록
+    choices: BTreeMap<String, Vec<String>>,
     _table: PhantomData<Field>,
 }
 
+/// 문서를 소유하는 테이블 객체입니다.
+///
+/// `Send + Sync + 'static`을 만족하므로, worker 내부에 보관하여 매 tick마다 필드를 다시 불러오지 않아도 됩니다.
+pub type OwnedTable<Field> = Table<'static, Field>;
+
 impl<'a, Field> Table<'a, Field> {
+    fn from_layout(
+        spreadsheet: Cow<'a, Spreadsheet>,
+        layout: TableLayout,
+        labels: BTreeMap<String, String>,
+    ) -> Self {
+        if !layout.drift.is_empty() {
+            warn!(
+                "the fields on \"{}\" do not match: {}",
+                &layout.fields_shape, &layout.drift
+            );
+        }
+
+        Self {
+            spreadsheet,
+            fields: layout.fields,
+            fields_shape: layout.fields_shape,
+            labels,
+            format: Default::default(),
+            formats: Default::default(),
+            drift: layout.drift,
+            strict: false,
+            protection: None,
+            choices: Default::default(),
+            _table: PhantomData::<Field>::default(),
+        }
+    }
+
+    /// 문서를 소유하는 테이블 객체로 변환합니다.
+    pub fn into_owned(self) -> OwnedTable<Field> {
+        Table {
+            spreadsheet: Cow::Owned(self.spreadsheet.into_owned()),
+            fields: self.fields,
+            fields_shape: self.fields_shape,
+            labels: self.labels,
+            format: self.format,
+            formats: self.formats,
+            drift: self.drift,
+            strict: self.strict,
+            protection: self.protection,
+            choices: self.choices,
+            _table: PhantomData::<Field>::default(),
+        }
+    }
+
+    /// 엄격한 검사를 활성화합니다.
+    ///
+    /// 필드 행에 알 수 없는 열이나 중복된 필드가 있거나 생략 가능한 필드가 없다면 실패하며,
+    /// 이후 `revalidate`에도 적용됩니다.
+    pub fn strict(mut self) -> Result<Self> {
+        self.drift.ensure_strict(&self.fields_shape)?;
+        self.strict = true;
+        Ok(self)
+    }
+
+    /// 필드 행과 구조체의 필드 사이의 차이를 반환합니다.
+    pub fn drift(&self) -> &SchemaDrift {
+        &self.drift
+    }
+
+    /// 테이블의 기본 셀 형식을 설정합니다.
+    pub fn with_format(mut self, format: CellFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// 주어진 필드의 셀 형식을 설정합니다.
+    pub fn with_column_format(mut self, field: impl ToString, format: CellFormat) -> Self {
+        self.formats.insert(field.to_string(), format);
+        self
+    }
+
+    /// 체크박스가 설정된 참/거짓 필드를 찾아, 해당 필드의 셀 형식을 체크박스 형식으로 설정합니다.
+    ///
+    /// 첫번째 행의 데이터 유효성 검사 규칙을 기준으로 판단합니다.
+    pub async fn detect_checkboxes(&mut self) -> Result<()> {
+        let range = self.values_shape(0, Some(0)).to_string();
+        let (_, ret) = self
+            .spreadsheet
+            .hub()?
+            .spreadsheets()
+            .get(&self.spreadsheet.id)
+            .add_ranges(&range)
+            .include_grid_data(true)
+            .param(
+                "fields",
+                "sheets.data.rowData.values.dataValidation.condition.type",
+            )
+            .doit()
+            .await?;
+
+        let cells = ret
+            .sheets
+            .unwrap_or_default()
+            .into_iter()
+            .flat_map(|sheet| sheet.data.unwrap_or_default())
+            .flat_map(|data| data.row_data.unwrap_or_default())
+            .next()
+            .and_then(|row| row.values)
+            .unwrap_or_default();
+        for field in &self.fields {
+            let ty = cells
+                .get(field.col as usize)
+                .and_then(|cell| cell.data_validation.clone())
+                .and_then(|rule| rule.condition)
+                .and_then(|condition| condition.type_);
+            if field.ty == InstanceType::Boolean && ty.as_deref() == Some("BOOLEAN") {
+                let format = CellFormat {
+                    null_tokens: self.format.null_tokens.clone(),
+                    ..CellFormat::checkbox()
+                };
+                self.formats.insert(field.field.clone(), format);
+            }
+        }
+        Ok(())
+    }
+
+    fn format_of(&self, field: &FieldName) -> &CellFormat {
+        self.formats.get(&field.field).unwrap_or(&self.format)
+    }
+
+    /// 테이블이 속한 문서를 반환합니다.
+    pub fn spreadsheet(&self) -> &Spreadsheet {
+        &self.spreadsheet
+    }
+
+    /// 테이블의 필드 목록을 반환합니다.
+    pub fn fields(&self) -> &[FieldName] {
+        &self.fields
+    }
+
+    /// 필드 행을 다시 불러와, 저장된 필드 구조가 여전히 유효한지 확인합니다.
+    ///
+    /// 필드 구조가 바뀌었다면 이를 갱신하고 `true`를 반환합니다.
+    /// 보호 범위나 선택할 수 있는 값 목록이 설정되어 있다면, 바뀐 필드 구조에 맞게 다시 반영합니다.
+    pub async fn revalidate(&mut self) -> Result<bool>
+    where
+        Field: JsonSchema,
+    {
+        let matrix = self.spreadsheet.get(&self.fields_shape.to_string()).await?;
+        let layout = parse_layout::<Field>(matrix, &self.labels)?;
+        if self.strict {
+            layout.drift.ensure_strict(&layout.fields_shape)?;
+        }
+        if layout.fields == self.fields && layout.fields_shape == self.fields_shape {
+            return Ok(false);
+        }
+        if layout.drift != self.drift && !layout.drift.is_empty() {
+            warn!(
+                "the fields on \"{}\" do not match: {}",
+                &layout.fields_shape, &layout.drift
+            );
+        }
+
+        self.fields = layout.fields;
+        self.fields_shape = layout.fields_shape;
+        self.drift = layout.drift;
+        self.sync_protections().await?;
+        self.sync_choices().await?;
+        Ok(true)
+    }
+
     /// 테이블 객체를 불러옵니다.
     pub async fn get_rows(&self, length: Option<u32>) -> Result<Vec<Field>>
     where
         Field: DeserializeOwned,
     {
-        fn parse_col(field: &FieldName, token: String) -> Result<Value> {
-            match token.as_str() {
-                "" | "N/A" => Ok(Value::Null),
-                _ => match field.ty {
-                    InstanceType::Null => Ok(Value::Null),
-                    InstanceType::Boolean => match token.trim().to_uppercase().as_str() {
-                        "TRUE" | "YES" | "Y" | "O" | "V" => Ok(Value::Bool(true)),
-                        "FALSE" | "NO" | "N" | "X" => Ok(Value::Bool(false)),
-                        _ => bail!(
-                            "cannot parse the value into boolean \"{}\" ({})",
-                            token,
-                            &field.name
-                        ),
-                    },
-                    InstanceType::Integer | InstanceType::Number => {
-                        Ok(Value::Number(token.parse()?))
-                    }
-                    // TODO: to be implemented
-                    InstanceType::String => Ok(Value::String(token)),
-                    InstanceType::Array => todo!(),
-                    InstanceType::Object => unreachable!("Object type should be pruned"),
-                },
-            }
+        let snapshot = self.get_rows_snapshot(length).await?;
+        if let Some(saved_at) = &snapshot.stale_since {
+            warn!(
+                "serving stale rows of {} (saved at {:?})",
+                &self.fields_shape, saved_at
+            );
         }
+        Ok(snapshot.data)
+    }
 
+    /// 테이블 객체를 불러오며, 로컬 저장소의 값을 대신 반환했는지 여부를 함께 반환합니다.
+    pub async fn get_rows_snapshot(&self, length: Option<u32>) -> Result<Snapshot<Vec<Field>>>
+    where
+        Field: DeserializeOwned,
+    {
+        let snapshot = self.read_rows(length, false).await?;
+        Ok(snapshot.map(|rows| rows.into_iter().map(|row| row.data).collect()))
+    }
+
+    /// 빈 행을 제외한 테이블 객체를 위치와 함께 불러옵니다.
+    ///
+    /// 시트의 남은 행들과 같이 모든 필드의 셀이 빈 행은 해석하지 않고 건너뜁니다.
+    pub async fn get_filled_rows(&self, length: Option<u32>) -> Result<Vec<Row<Field>>>
+    where
+        Field: DeserializeOwned,
+    {
+        let snapshot = self.read_rows(length, true).await?;
+        if let Some(saved_at) = &snapshot.stale_since {
+            warn!(
+                "serving stale rows of {} (saved at {:?})",
+                &self.fields_shape, saved_at
+            );
+        }
+        Ok(snapshot.data)
+    }
+
+    async fn read_rows(
+        &self,
+        length: Option<u32>,
+        skip_blank: bool,
+    ) -> Result<Snapshot<Vec<Row<Field>>>>
+    where
+        Field: DeserializeOwned,
+    {
         if length == Some(0) {
-            return Ok(vec![]);
+            return Ok(Snapshot::fresh(vec![]));
         }
-        let matrix = self
+        let range = self.values_shape(0, length.map(|e| e - 1)).to_string();
+        let snapshot = self
             .spreadsheet
-            .get(&self.values_shape(0, length.map(|e| e - 1)).to_string())
+            .get_snapshot_with(&range, Some(&self.layout_key()))
             .await?;
 
+        // 로컬 저장소의 값에는 링크 주소가 없으므로, 표시되는 문자열만 사용합니다.
+        let stale_since = snapshot.stale_since;
+        let links = match &stale_since {
+            Some(_) => vec![],
+            None => self.get_links(&[range]).await?.pop().unwrap_or_default(),
+        };
+
         // TODO: Vector notation
-        matrix
+        let data = snapshot
+            .data
             .into_iter()
-            .map(|cols| {
-                let fields: BTreeMap<_, _> = self
-                    .fields
-                    .iter()
-                    .zip(cols)
-                    .map(|(k, v)| Ok((&k.field, parse_col(&k, v)?)))
-                    .collect::<Result<_>>()?;
-                Ok(serde_json::from_value(serde_json::to_value(fields)?)?)
+            .enumerate()
+            .filter(|(_, cols)| !skip_blank || !self.is_blank(cols))
+            .map(|(index, cols)| {
+                let urls = links.get(index).map(Vec::as_slice).unwrap_or_default();
+                let fields = self.parse_values(cols, urls)?;
+                Ok(Row {
+                    index: index as u32,
+                    data: serde_json::from_value(serde_json::to_value(fields)?)?,
+                })
             })
-            .collect()
+            .collect::<Result<_>>()?;
+        Ok(Snapshot { data, stale_since })
+    }
+
+    /// 모든 필드의 셀이 비어있는지 여부를 반환합니다.
+    fn is_blank(&self, cols: &[String]) -> bool {
+        self.fields.iter().all(|field| {
+            cols.get(field.col as usize)
+                .map_or(true, |token| token.trim().is_empty())
+        })
     }
 
     /// 테이블 객체를 반영합니다.
-    pub async fn set_rows(&self, rows: &[Field], offset: u32) -> Result<()>
+    ///
+    /// 연결 문제로 반영하지 못하고 대기열에 저장했다면 `WriteStatus::Queued`를 반환합니다.
+    pub async fn set_rows(&self, rows: &[Field], offset: u32) -> Result<WriteStatus>
     where
         Field: Serialize,
     {
-        fn parse_col(field: &FieldName, value: Value) -> Result<String> {
-            match value {
-                Value::Null => Ok("N/A".to_string()),
-                Value::Bool(value) => Ok(if value { "Y" } else { "N" }.to_string()),
-                Value::Number(value) => Ok(value.to_string()),
-                Value::String(value) => Ok(value),
-                // TODO: to be implemented
-                Value::Array(values) => todo!(),
-                Value::Object(_) => unreachable!("Object type should be pruned"),
+        let matrices = self.render_rows(rows, offset)?;
+        self.spreadsheet.update_many(matrices).await
+    }
+
+    /// 불러온 이후의 변경 사항과 충돌하지 않도록 테이블 객체를 반영합니다.
+    ///
+    /// `base`는 이전에 `get_rows`로 불러온 값이며, `rows`는 반영하고자 하는 값입니다.
+    /// 반영 직전에 문서를 다시 읽어, 표시 형식과 무관하게 해석한 값을 셀 단위로 비교합니다.
+    /// * `rows`에서 바뀌지 않은 셀은 다시 쓰지 않습니다.
+    /// * 사람이 수정하지 않은 셀은 `rows`의 값으로 덮어씁니다.
+    /// * 사람만 수정한 셀은 현재 문서의 값을 유지합니다.
+    /// * 양쪽 모두 서로 다른 값으로 수정한 셀은 충돌로 간주합니다.
+    ///
+    /// 충돌이 발생한 경우의 동작은 `policy`를 따르며, 발견된 충돌 목록과 쓰기 요청의 결과를 반환합니다.
+    ///
+    /// 저장된 읽기 결과와 비교하면 그 이후의 수정을 덮어쓸 수 있으므로,
+    /// 로컬 저장소의 값을 사용하지 않으며 쓰기 요청을 대기열에 저장하지도 않습니다.
+    /// 연결 문제로 문서를 읽거나 쓰지 못했다면 아무 값도 반영하지 않고 `WriteStatus::Offline`을 반환하므로,
+    /// 연결이 복구된 후 다시 호출해야 합니다.
+    pub async fn set_rows_checked(
+        &self,
+        base: &[Field],
+        rows: &[Field],
+        offset: u32,
+        policy: ConflictPolicy,
+    ) -> Result<CheckedWrite>
+    where
+        Field: Serialize,
+    {
+        if base.len() != rows.len() {
+            bail!(
+                "the number of base rows ({}) and new rows ({}) should be equal",
+                base.len(),
+                rows.len(),
+            );
+        }
+        if rows.is_empty() {
+            return Ok(CheckedWrite {
+                conflicts: vec![],
+                status: Some(WriteStatus::Written),
+            });
+        }
+
+        let row_start = offset;
+        let row_end = offset + rows.len() as u32 - 1;
+        let range = self.values_shape(row_start, Some(row_end)).to_string();
+        let read = async {
+            let current = self.spreadsheet.get_fresh(&range).await?;
+            let links = self.get_links(std::slice::from_ref(&range)).await?;
+            Ok::<_, anyhow::Error>((current, links))
+        };
+        let (current, links) = match read.await {
+            Ok((current, mut links)) => (current, links.pop().unwrap_or_default()),
+            Err(error) if is_transient(&error) => {
+                warn!(
+                    "failed to read {}, skipped the checked write: {}",
+                    &range, error
+                );
+                return Ok(CheckedWrite::offline());
             }
+            Err(error) => return Err(error),
+        };
+
+        let mut conflicts = vec![];
+        let mut data = vec![];
+        for (index, ((base, ours), mut current)) in base.iter().zip(rows).zip(current).enumerate() {
+            let mut base = into_object(serde_json::to_value(base)?);
+            let mut ours = into_object(serde_json::to_value(ours)?);
+
+            let urls = links.get(index).map(Vec::as_slice).unwrap_or_default();
+            let mut cols = vec![];
+            for field in &self.fields {
+                let token = current
+                    .get_mut(field.col as usize)
+                    .map(std::mem::take)
+                    .unwrap_or_default();
+                let base =
+                    self.normalize(field, base.remove(&field.field).unwrap_or(Value::Null))?;
+                let ours =
+                    self.normalize(field, ours.remove(&field.field).unwrap_or(Value::Null))?;
+                let theirs = self
+                    .parse_value(field, token.clone(), urls)
+                    .map(normalize_number)
+                    .unwrap_or(Value::String(token));
+
+                // 바꾸지 않는 셀은 다시 쓰지 않으므로, 수식이나 링크가 그대로 유지됩니다.
+                if ours == base || ours == theirs {
+                    cols.push(None);
+                } else if theirs == base {
+                    cols.push(Some(self.format_of(field).render(field, ours)?));
+                } else {
+                    conflicts.push(Conflict {
+                        row: offset + index as u32,
+                        field: field.field.clone(),
+                        base,
+                        theirs,
+                        ours,
+                    });
+                    cols.push(None);
+                }
+            }
+            data.push(cols);
         }
 
-        fn parse_field(fields: &[FieldName], field: Value) -> Result<Vec<String>> {
-            match field {
-                Value::Object(mut cols) => fields
-                    .iter()
-                    .map(|field| (field, cols.remove(&field.field).unwrap()))
-                    .map(|(field, value)| parse_col(field, value))
-                    .collect(),
-                _ => unreachable!("Object type should be pruned"),
+        if !conflicts.is_empty() && policy == ConflictPolicy::Refuse {
+            return Ok(CheckedWrite {
+                conflicts,
+                status: None,
+            });
+        }
+        let matrices = self.split_cells(offset, data);
+        match self.spreadsheet.update_many_remote(matrices).await {
+            Ok(()) => Ok(CheckedWrite {
+                conflicts,
+                status: Some(WriteStatus::Written),
+            }),
+            Err(error) if is_transient(&error) => {
+                warn!(
+                    "failed to write {}, skipped the checked write: {}",
+                    &range, error
+                );
+                Ok(CheckedWrite::offline())
             }
+            Err(error) => Err(error),
+        }
+    }
+
+    /// 셀에 쓴 후 다시 읽은 것처럼 값을 정규화하여, 표시 형식과 무관하게 비교할 수 있도록 합니다.
+    fn normalize(&self, field: &FieldName, value: Value) -> Result<Value> {
+        if value.is_object() {
+            return Ok(value);
         }
+        let format = self.format_of(field);
+        let token = format.render(field, value)?;
+        let value = format
+            .parse(field, token.clone())
+            .unwrap_or(Value::String(token));
+        Ok(normalize_number(value))
+    }
+
+    fn render_rows(&self, rows: &[Field], offset: u32) -> Result<Vec<Matrix>>
+    where
+        Field: Serialize,
+    {
         // TODO: Vector notation
         let data: Vec<_> = rows
             .into_iter()
             .map(|field| {
                 serde_json::to_value(field)
                     .map_err(Into::into)
-                    .and_then(|field| parse_field(&self.fields, field))
+                    .and_then(|field| self.render_values(field))
             })
             .collect::<Result<_>>()?;
         if data.is_empty() {
-            return Ok(());
+            return Ok(vec![]);
+        }
+        Ok(self.split_cols(offset, data))
+    }
+
+    fn parse_values(
+        &self,
+        mut cols: Vec<String>,
+        urls: &[Option<String>],
+    ) -> Result<BTreeMap<&String, Value>> {
+        self.fields
+            .iter()
+            .map(|k| {
+                let v = cols
+                    .get_mut(k.col as usize)
+                    .map(std::mem::take)
+                    .unwrap_or_default();
+                Ok((&k.field, self.parse_value(k, v, urls)?))
+            })
+            .collect()
+    }
+
+    /// 셀의 값을 해석합니다. 링크 필드라면 같은 열의 링크 주소를 함께 사용합니다.
+    fn parse_value(
+        &self,
+        field: &FieldName,
+        token: String,
+        urls: &[Option<String>],
+    ) -> Result<Value> {
+        let mut value = self.format_of(field).parse(field, token)?;
+        if let Value::Object(link) = &mut value {
+            if let Some(Some(url)) = urls.get(field.col as usize) {
+                link.insert("url".to_string(), Value::String(url.clone()));
+            }
         }
+        Ok(value)
+    }
 
+    /// 필드 순서로 정렬된 값들을, 연속된 열 단위로 나누어 반영할 행렬 목록을 만듭니다.
+    ///
+    /// 구조체에 존재하지 않는 열은 반영하지 않습니다.
+    fn split_cols(&self, offset: u32, data: Vec<Vec<String>>) -> Vec<Matrix> {
         let row_start = offset;
         let row_end = offset + data.len() as u32 - 1;
-        let matrix = Matrix {
-            shape: self.values_shape(row_start, Some(row_end)),
-            data,
-        };
-        self.spreadsheet.update(matrix).await
+
+        let mut matrices = vec![];
+        let mut begin = 0;
+        while begin < self.fields.len() {
+            let mut end = begin + 1;
+            while end < self.fields.len() && self.fields[end].col == self.fields[end - 1].col + 1 {
+                end += 1;
+            }
+
+            let mut shape = self.values_shape(row_start, Some(row_end));
+            shape.start.col += self.fields[begin].col;
+            shape.end.col = shape.start.col + (end - begin - 1) as u16;
+            matrices.push(Matrix {
+                shape,
+                data: data.iter().map(|row| row[begin..end].to_vec()).collect(),
+            });
+            begin = end;
+        }
+        matrices
+    }
+
+    /// 필드 순서로 정렬된 값들 중 주어진 셀만, 행마다 연속된 열 단위로 나누어 반영할 행렬 목록을 만듭니다.
+    fn split_cells(&self, offset: u32, data: Vec<Vec<Option<String>>>) -> Vec<Matrix> {
+        let mut matrices = vec![];
+        for (index, mut cols) in data.into_iter().enumerate() {
+            let row = offset + index as u32;
+            let mut begin = 0;
+            while begin < self.fields.len() {
+                if cols[begin].is_none() {
+                    begin += 1;
+                    continue;
+                }
+                let mut end = begin + 1;
+                while end < self.fields.len()
+                    && cols[end].is_some()
+                    && self.fields[end].col == self.fields[end - 1].col + 1
+                {
+                    end += 1;
+                }
+
+                let mut shape = self.values_shape(row, Some(row));
+                shape.start.col += self.fields[begin].col;
+                shape.end.col = shape.start.col + (end - begin - 1) as u16;
+                matrices.push(Matrix {
+                    shape,
+                    data: vec![cols[begin..end].iter_mut().flat_map(Option::take).collect()],
+                });
+                begin = end;
+            }
+        }
+        matrices
+    }
+
+    fn render_values(&self, field: Value) -> Result<Vec<String>> {
+        let mut cols = into_object(field);
+        self.fields
+            .iter()
+            .map(|field| (field, cols.remove(&field.field).unwrap()))
+            .map(|(field, value)| self.format_of(field).render(field, value))
+            .collect()
+    }
+
+    /// 로컬 저장소에 저장된 읽기 결과를 구분하기 위한 필드 구조입니다.
+    fn layout_key(&self) -> String {
+        self.fields
+            .iter()
+            .map(|field| format!("{}:{}", field.col, &field.field))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    fn values_row(&self, row: u32) -> u32 {
+        self.fields_shape.end.row.unwrap() + 1 + row
     }
 
     fn values_start(&self, row: u32) -> MatrixIndex {
         MatrixIndex {
             col: self.fields_shape.start.col,
-            row: Some(self.fields_shape.end.row.unwrap() + 1 + row),
+            row: Some(self.values_row(row)),
         }
     }
 
     fn values_end(&self, row: Option<u32>) -> MatrixIndex {
         MatrixIndex {
             col: self.fields_shape.end.col,
-            row: row.map(|row| (self.fields_shape.end.row.unwrap() + 1 + row)),
+            row: row.map(|row| self.values_row(row)),
         }
     }
 
@@ -353,11 +1188,366 @@ impl<'a, Field> Table<'a, Field> {
     }
 }
 
-#[derive(Clone, Debug)]
+struct TableLayout {
+    fields: Vec<FieldName>,
+    fields_shape: MatrixShape,
+    drift: SchemaDrift,
+}
+
+/// 필드 행을 해석합니다.
+///
+/// `labels`는 필드 행에 표시되는 이름으로부터 구조체의 필드 이름으로의 대응이며,
+/// 주어지지 않은 이름은 그대로, 또는 snake_case로 변환하여 필드 이름과 비교합니다.
+fn parse_layout<Field>(
+    mut fields_matrix: Matrix,
+    labels: &BTreeMap<String, String>,
+) -> Result<TableLayout>
+where
+    Field: JsonSchema,
+{
+    fn parse_type(name: &str, ty: InstanceType, format: Option<&str>) -> Result<InstanceType> {
+        match ty {
+            InstanceType::Object if format == Some(Link::FORMAT) => Ok(ty),
+            InstanceType::Object => bail!("child struct is not supported"),
+            InstanceType::Array => bail!("array fields are not supported: {}", name),
+            _ => Ok(ty),
+        }
+    }
+
+    fn parse_schema(
+        name: String,
+        required: bool,
+        schema: &Schema,
+        definitions: &schemars::Map<String, Schema>,
+    ) -> Result<FieldSchema> {
+        match resolve_schema(schema, definitions) {
+            None => {
+                bail!("a trivial boolean JSON Schema is not supported: {}", name)
+            }
+            Some(schema) => {
+                let ty = schema
+                    .instance_type
+                    .clone()
+                    .or_else(|| {
+                        // 각 값에 설명이 붙은 열거형은 타입 없이 `oneOf`로 표현됩니다.
+                        if schema_enum_values(schema, definitions).is_empty() {
+                            None
+                        } else {
+                            Some(InstanceType::String.into())
+                        }
+                    })
+                    .ok_or_else(|| anyhow!("cannot infer the type: {}", name))
+                    .and_then(|types| match types {
+                        SingleOrVec::Single(ty) => Ok(*ty),
+                        SingleOrVec::Vec(types) => {
+                            let mut types =
+                                types.into_iter().filter(|ty| *ty != InstanceType::Null);
+                            let ty = types.next().unwrap_or(InstanceType::Null);
+                            match types.next() {
+                                Some(_) => {
+                                    bail!("2 or more types in one type is not supported: {}", name)
+                                }
+                                None => Ok(ty),
+                            }
+                        }
+                    })
+                    .and_then(|ty| parse_type(&name, ty, schema.format.as_deref()))?;
+                Ok(FieldSchema {
+                    name,
+                    ty,
+                    format: schema.format.clone(),
+                    required,
+                })
+            }
+        }
+    }
+
+    fn parse_object_properties(
+        object: Box<ObjectValidation>,
+        definitions: &schemars::Map<String, Schema>,
+    ) -> Result<Vec<FieldSchema>> {
+        let required = object.required;
+        object
+            .properties
+            .into_iter()
+            .map(|(name, schema)| {
+                let is_required = required.contains(&name);
+                parse_schema(name, is_required, &schema, definitions)
+            })
+            .collect()
+    }
+
+    fn parse_matrix(
+        struct_name: &str,
+        fields_struct: Vec<FieldSchema>,
+        labels: &BTreeMap<String, String>,
+        matrix: &mut Matrix,
+    ) -> Result<(Vec<FieldName>, SchemaDrift)> {
+        let mut fields: Vec<FieldName> = vec![];
+        let mut drift = SchemaDrift::default();
+        for (col, names) in matrix.cols().enumerate() {
+            let names: Vec<_> = names
+                .into_iter()
+                .map(|name| name.trim().to_string())
+                .filter(|name| !name.is_empty())
+                .collect();
+            let field = names
+                .iter()
+                // serde로 이름을 바꾼 필드를 위해, 표시된 이름을 그대로 먼저 비교합니다.
+                .flat_map(|name| match labels.get(name) {
+                    Some(field) => vec![field.clone()],
+                    None => vec![name.clone(), name.to_snake_case()],
+                })
+                .filter_map(|name| {
+                    fields_struct
+                        .iter()
+                        .find(|field| name == field.name)
+                        .map(|field| FieldName {
+                            name,
+                            field: field.name.clone(),
+                            ty: field.ty,
+                            format: field.format.clone(),
+                            col: col as u16,
+                        })
+                })
+                .next();
+
+            match field {
+                Some(field) if fields.iter().any(|e| e.field == field.field) => {
+                    drift.duplicated.push(field.field)
+                }
+                Some(field) => fields.push(field),
+                None => drift.unknown.extend(names.into_iter().next()),
+            }
+        }
+
+        // 생략할 수 없는 필드가 없다면 어떤 행도 읽을 수 없으므로 실패하며,
+        // 생략 가능한 필드는 차이로만 기록합니다.
+        let (missing_required, missing): (Vec<_>, Vec<_>) = fields_struct
+            .iter()
+            .filter(|field| !fields.iter().any(|e| e.field == field.name))
+            .partition(|field| field.required);
+        if !missing_required.is_empty() {
+            let names: Vec<_> = missing_required
+                .iter()
+                .map(|field| field.name.as_str())
+                .collect();
+            bail!(
+                "cannot find the field(s) \"{}\" for \"{}\" on \"{}\"",
+                names.join("\", \""),
+                struct_name,
+                &matrix.shape,
+            );
+        }
+        drift.missing = missing
+            .into_iter()
+            .map(|field| field.name.clone())
+            .collect();
+
+        // 없는 필드는 순서를 비교하지 않습니다.
+        let expected = fields_struct
+            .iter()
+            .filter(|field| !drift.missing.contains(&field.name));
+        drift.reordered = fields
+            .iter()
+            .zip(expected)
+            .filter(|(field, expected)| field.field != expected.name)
+            .map(|(field, _)| field.field.clone())
+            .collect();
+        Ok((fields, drift))
+    }
+
+    let root = schemars::schema_for!(Field);
+    let mut schema = root.schema;
+    let name = schema
+        .metadata()
+        .title
+        .clone()
+        .unwrap_or_else(|| "unknown field".to_string());
+    match schema.object {
+        Some(object) => {
+            let fields_struct = parse_object_properties(object, &root.definitions)?;
+            let (fields, drift) = parse_matrix(&name, fields_struct, labels, &mut fields_matrix)?;
+
+            Ok(TableLayout {
+                fields,
+                fields_shape: fields_matrix.shape,
+                drift,
+            })
+        }
+        None => bail!(
+            "field {} is not a struct (not supported: enum, union, ...)",
+            name
+        ),
+    }
+}
+
+/// 다른 정의를 참조하거나 빈 값을 허용하는 스키마를 풀어, 실제 값의 스키마를 반환합니다.
+///
+/// 열거형과 같이 별도로 정의된 타입이나, 그러한 타입의 `Option`을 필드로 사용할 수 있도록 합니다.
+pub(crate) fn resolve_schema<'s>(
+    schema: &'s Schema,
+    definitions: &'s schemars::Map<String, Schema>,
+) -> Option<&'s SchemaObject> {
+    let schema = match schema {
+        Schema::Bool(_) => return None,
+        Schema::Object(schema) => schema,
+    };
+
+    if let Some(reference) = &schema.reference {
+        let name = reference.trim_start_matches("#/definitions/");
+        return resolve_schema(definitions.get(name)?, definitions);
+    }
+    if let Some(subschemas) = &schema.subschemas {
+        let inner = subschemas
+            .all_of
+            .as_ref()
+            .or_else(|| subschemas.any_of.as_ref())
+            .map(|schemas| {
+                schemas
+                    .iter()
+                    .filter(|schema| !is_null_schema(schema))
+                    .collect::<Vec<_>>()
+            });
+        if let Some([inner]) = inner.as_deref() {
+            return resolve_schema(*inner, definitions);
+        }
+    }
+    Some(schema)
+}
+
+fn is_null_schema(schema: &Schema) -> bool {
+    match schema {
+        Schema::Object(schema) => {
+            schema.instance_type == Some(SingleOrVec::Single(Box::new(InstanceType::Null)))
+        }
+        Schema::Bool(_) => false,
+    }
+}
+
+/// 구조체를 직렬화한 값을 필드 이름과 값의 목록으로 바꿉니다.
+pub fn into_object(field: Value) -> serde_json::Map<String, Value> {
+    match field {
+        Value::Object(cols) => cols,
+        _ => unreachable!("Object type should be pruned"),
+    }
+}
+
+/// 셀에 입력된 값을, `USER_ENTERED`로 다시 쓰면 같은 값이 되는 문자열로 바꿉니다.
+fn entered_value(value: Option<ExtendedValue>) -> String {
+    let value = match value {
+        Some(value) => value,
+        None => return String::new(),
+    };
+    if let Some(formula) = value.formula_value {
+        formula
+    } else if let Some(number) = value.number_value {
+        number.to_string()
+    } else if let Some(value) = value.bool_value {
+        (if value { "TRUE" } else { "FALSE" }).to_string()
+    } else {
+        match value.string_value {
+            Some(text) if !text.is_empty() => format!("'{}", text),
+            _ => String::new(),
+        }
+    }
+}
+
+/// 범위 표기에서 시트 이름을 꺼냅니다.
+fn range_title(range: &str) -> String {
+    let title = range.split('!').next().unwrap_or_default();
+    match title.strip_prefix('\'').and_then(|e| e.strip_suffix('\'')) {
+        Some(title) => title.replace("''", "'"),
+        None => title.to_string(),
+    }
+}
+
+/// 숫자를 실수로 바꾸어, 같은 값이 정수와 실수로 다르게 표현되더라도 같게 비교되도록 합니다.
+fn normalize_number(value: Value) -> Value {
+    match value.as_f64().and_then(serde_json::Number::from_f64) {
+        Some(number) => Value::Number(number),
+        None => value,
+    }
+}
+
+/// 셀 단위의 쓰기 충돌 정보입니다.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Conflict {
+    /// 충돌이 발생한 행 (테이블 기준 offset)
+    pub row: u32,
+    /// 충돌이 발생한 필드
+    pub field: String,
+    /// 이전에 불러온 값
+    pub base: Value,
+    /// 현재 문서에 저장된 값
+    pub theirs: Value,
+    /// 반영하고자 하는 값
+    pub ours: Value,
+}
+
+/// 충돌을 확인하며 반영한 결과입니다.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CheckedWrite {
+    /// 발견된 충돌 목록
+    pub conflicts: Vec<Conflict>,
+    /// 쓰기 요청의 결과 (충돌로 인해 아무 값도 반영하지 않았다면 `None`)
+    pub status: Option<WriteStatus>,
+}
+
+impl CheckedWrite {
+    fn offline() -> Self {
+        Self {
+            conflicts: vec![],
+            status: Some(WriteStatus::Offline),
+        }
+    }
+}
+
+/// 쓰기 요청의 결과입니다.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum WriteStatus {
+    /// 문서에 반영했습니다.
+    Written,
+    /// 연결 문제로 대기열에 저장했으며, 연결이 복구되면 반영합니다.
+    Queued,
+    /// 연결 문제로 아무 값도 반영하지 않았으며, 대기열에도 저장하지 않았습니다.
+    Offline,
+}
+
+/// 쓰기 충돌이 발생한 경우의 동작입니다.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// 충돌이 하나라도 있으면 아무 값도 반영하지 않습니다.
+    Refuse,
+    /// 충돌하지 않는 값만 반영하고, 충돌한 셀은 현재 문서의 값을 유지합니다.
+    Merge,
+}
+
+/// 구조체에 선언된 필드의 이름과 타입입니다.
+struct FieldSchema {
+    name: String,
+    ty: InstanceType,
+    format: Option<String>,
+    /// 생략할 수 없는 필드인지 여부 (`Option`이나 `#[serde(default)]`가 아닌 필드)
+    required: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct FieldName {
     name: String,
     field: String,
     ty: InstanceType,
+    /// JSON Schema의 `format` (예: `hyperlink`)
+    format: Option<String>,
+    /// 필드 행의 첫번째 열을 기준으로 한 열 번호
+    col: u16,
+}
+
+impl FieldName {
+    /// 하이퍼링크 필드인지 여부를 반환합니다.
+    pub(crate) fn is_link(&self) -> bool {
+        self.format.as_deref() == Some(Link::FORMAT)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -510,7 +1700,7 @@ pub mod iter {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct MatrixShape {
     pub sheet: String,
     pub start: MatrixIndex,
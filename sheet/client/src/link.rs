@@ -0,0 +1,141 @@
+use anyhow::Result;
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{InstanceType, Schema, SchemaObject},
+    JsonSchema,
+};
+
+#[cfg(feature = "testing")]
+use crate::Backend;
+use crate::{FieldName, Spreadsheet, Table};
+
+/// 하이퍼링크 셀입니다.
+///
+/// `HYPERLINK()` 수식이나 서식 있는 텍스트의 링크를 읽을 수 있으며, `HYPERLINK()` 수식으로 씁니다.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Link {
+    /// 셀에 표시되는 문자열
+    pub label: String,
+    /// 링크 주소
+    pub url: Option<String>,
+}
+
+impl Link {
+    pub(crate) const FORMAT: &'static str = "hyperlink";
+
+    pub fn new(label: impl ToString, url: impl ToString) -> Self {
+        Self {
+            label: label.to_string(),
+            url: Some(url.to_string()),
+        }
+    }
+
+    pub(crate) fn to_formula(&self) -> String {
+        fn escape(text: &str) -> String {
+            text.replace('"', "\"\"")
+        }
+
+        match &self.url {
+            Some(url) => format!(
+                "=HYPERLINK(\"{}\", \"{}\")",
+                escape(url),
+                escape(&self.label)
+            ),
+            None => self.label.clone(),
+        }
+    }
+}
+
+impl JsonSchema for Link {
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn schema_name() -> String {
+        "Link".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(InstanceType::Object.into()),
+            format: Some(Self::FORMAT.to_string()),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+/// 셀 단위의 링크 주소 목록입니다. (범위 > 행 > 열)
+pub(crate) type Links = Vec<Vec<Vec<Option<String>>>>;
+
+impl Spreadsheet {
+    /// 주어진 범위들에 포함된 셀들의 링크 주소를 불러옵니다.
+    ///
+    /// `HYPERLINK()` 수식의 주소를 우선하며, 없다면 서식 있는 텍스트의 첫번째 링크 주소를 사용합니다.
+    pub(crate) async fn get_links_many(&self, ranges: &[String]) -> Result<Links> {
+        if ranges.is_empty() {
+            return Ok(vec![]);
+        }
+        // 메모리에 저장되는 문서는 링크 주소를 저장하지 않습니다.
+        #[cfg(feature = "testing")]
+        if let Backend::Memory(_) = &self.backend {
+            return Ok(vec![vec![]; ranges.len()]);
+        }
+
+        let mut request = self.hub()?.spreadsheets().get(&self.id);
+        for range in ranges {
+            request = request.add_ranges(range);
+        }
+        let (_, ret) = request
+            .include_grid_data(true)
+            .param(
+                "fields",
+                "sheets.data.rowData.values(hyperlink,textFormatRuns.format.link.uri)",
+            )
+            .doit()
+            .await?;
+
+        Ok(ret
+            .sheets
+            .unwrap_or_default()
+            .into_iter()
+            .flat_map(|sheet| sheet.data.unwrap_or_default())
+            .map(|data| {
+                data.row_data
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|row| {
+                        row.values
+                            .unwrap_or_default()
+                            .into_iter()
+                            .map(|cell| {
+                                let runs = cell.text_format_runs;
+                                cell.hyperlink.or_else(|| {
+                                    runs.unwrap_or_default()
+                                        .into_iter()
+                                        .find_map(|run| run.format?.link?.uri)
+                                })
+                            })
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect())
+    }
+}
+
+impl<'a, Field> Table<'a, Field> {
+    /// 링크 필드의 열을 포함하는지 여부를 반환합니다.
+    pub(crate) fn has_links(&self) -> bool {
+        self.fields.iter().any(FieldName::is_link)
+    }
+
+    /// 링크 필드가 있다면, 주어진 범위들의 링크 주소를 불러옵니다.
+    pub(crate) async fn get_links(&self, ranges: &[String]) -> Result<Links> {
+        if self.has_links() {
+            self.spreadsheet.get_links_many(ranges).await
+        } else {
+            Ok(vec![])
+        }
+    }
+}
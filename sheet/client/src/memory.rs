@@ -0,0 +1,252 @@
+use std::{
+    collections::BTreeMap,
+    io,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use anyhow::Result;
+use google_sheets4::api::Request;
+
+use crate::{Matrix, MatrixShape};
+
+/// 메모리에 저장되는 문서들입니다.
+///
+/// Google API 없이 `Spreadsheet`를 사용할 수 있도록 하며, 주로 테스트에 사용합니다.
+/// 값을 읽고 쓰는 요청만 지원하며, 셀의 값은 입력한 그대로 저장합니다.
+/// * 수식을 계산하지 않으며, 링크, 서식, 보호 범위, 데이터 유효성 검사 등은 반영하지 않고 요청만 기록합니다.
+/// * 시트 복사나 행 식별 정보와 같이 문서 구조를 다루는 요청은 지원하지 않습니다.
+#[derive(Clone, Debug, Default)]
+pub struct MemorySheets {
+    spreadsheets: Arc<Mutex<BTreeMap<String, Vec<MemorySheet>>>>,
+    /// 문서마다 받은 `batchUpdate` 요청들
+    requests: Arc<Mutex<BTreeMap<String, Vec<Request>>>>,
+    /// 연결이 끊긴 상황을 흉내내는지 여부
+    offline: Arc<AtomicBool>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct MemorySheet {
+    title: String,
+    /// 시트의 행 수
+    ///
+    /// Google Sheets와 같이, 끝 행이 주어지지 않은 범위는 마지막 행까지 읽습니다.
+    rows: u32,
+    cells: Vec<Vec<String>>,
+}
+
+impl MemorySheet {
+    fn cell_mut(&mut self, row: u32, col: u16) -> &mut String {
+        let (row, col) = (row as usize, col as usize);
+        if self.cells.len() <= row {
+            self.cells.resize_with(row + 1, Default::default);
+        }
+        let cols = &mut self.cells[row];
+        if cols.len() <= col {
+            cols.resize_with(col + 1, Default::default);
+        }
+        self.rows = self.rows.max(row as u32 + 1);
+        &mut cols[col]
+    }
+
+    fn cell(&self, row: u32, col: u16) -> &str {
+        self.cells
+            .get(row as usize)
+            .and_then(|cols| cols.get(col as usize))
+            .map(String::as_str)
+            .unwrap_or_default()
+    }
+}
+
+impl MemorySheets {
+    /// 주어진 범위의 값을 읽습니다.
+    ///
+    /// Google Sheets와 같이, 각 행의 끝에 있는 빈 셀과 끝에 있는 빈 행은 제외합니다.
+    pub fn get(&self, spreadsheet_id: &str, range: &str) -> Result<Vec<Vec<String>>> {
+        Ok(self.read_unchecked(spreadsheet_id, &range.parse()?)?.data)
+    }
+
+    /// 주어진 범위에 값을 씁니다.
+    ///
+    /// 존재하지 않는 시트는 새로 만들며, 시트의 행 수는 필요한 만큼 늘어납니다.
+    pub fn set(&self, spreadsheet_id: &str, range: &str, data: Vec<Vec<String>>) -> Result<()> {
+        self.write_unchecked(
+            spreadsheet_id,
+            Matrix {
+                shape: range.parse()?,
+                data,
+            },
+        );
+        Ok(())
+    }
+
+    /// 시트의 행 수를 설정합니다.
+    ///
+    /// 끝 행이 주어지지 않은 범위를 읽을 때 반환하는 행의 수를 결정합니다.
+    pub fn set_rows(&self, spreadsheet_id: &str, title: &str, rows: u32) {
+        let mut spreadsheets = self.spreadsheets.lock().unwrap();
+        let sheet = Self::sheet_mut(&mut spreadsheets, spreadsheet_id, title);
+        sheet.rows = rows;
+    }
+
+    /// 연결이 끊긴 상황을 흉내냅니다.
+    ///
+    /// 연결이 끊긴 동안 `Spreadsheet`를 통한 요청은 연결 오류로 실패하며, `get`과 `set`은 그대로 동작합니다.
+    pub fn set_offline(&self, offline: bool) {
+        self.offline.store(offline, Ordering::SeqCst);
+    }
+
+    /// 연결이 끊긴 상황이라면, 실제 API와 같은 연결 오류를 반환합니다.
+    fn connect(&self) -> Result<()> {
+        if self.offline.load(Ordering::SeqCst) {
+            let error = io::Error::new(io::ErrorKind::NotConnected, "the spreadsheets are offline");
+            return Err(google_sheets4::Error::Io(error).into());
+        }
+        Ok(())
+    }
+
+    /// 주어진 문서가 받은 `batchUpdate` 요청들을 받은 순서로 반환합니다.
+    ///
+    /// 보호 범위, 데이터 유효성 검사, 행 식별 정보 등이 의도대로 요청되었는지 확인할 수 있습니다.
+    pub fn requests(&self, spreadsheet_id: &str) -> Vec<Request> {
+        self.requests
+            .lock()
+            .unwrap()
+            .get(spreadsheet_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn record(&self, spreadsheet_id: &str, requests: Vec<Request>) -> Result<()> {
+        self.connect()?;
+        self.requests
+            .lock()
+            .unwrap()
+            .entry(spreadsheet_id.to_string())
+            .or_default()
+            .extend(requests);
+        Ok(())
+    }
+
+    pub(crate) fn sheet_titles(&self, spreadsheet_id: &str) -> Vec<String> {
+        self.spreadsheets
+            .lock()
+            .unwrap()
+            .get(spreadsheet_id)
+            .map(|sheets| sheets.iter().map(|sheet| sheet.title.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn sheet_id(&self, spreadsheet_id: &str, title: &str) -> Option<i32> {
+        self.sheet_titles(spreadsheet_id)
+            .iter()
+            .position(|e| e == title.trim_matches('\''))
+            .map(|index| index as i32)
+    }
+
+    pub(crate) fn read(&self, spreadsheet_id: &str, shape: &MatrixShape) -> Result<Matrix> {
+        self.connect()?;
+        self.read_unchecked(spreadsheet_id, shape)
+    }
+
+    fn read_unchecked(&self, spreadsheet_id: &str, shape: &MatrixShape) -> Result<Matrix> {
+        let spreadsheets = self.spreadsheets.lock().unwrap();
+        let title = shape.sheet.trim_matches('\'');
+        let sheet = spreadsheets
+            .get(spreadsheet_id)
+            .and_then(|sheets| sheets.iter().find(|sheet| sheet.title == title))
+            .ok_or_else(|| anyhow!("Unable to parse range: {}", shape))?;
+
+        let start = shape.start.row.unwrap_or_default();
+        let end = match shape.end.row {
+            Some(end) => end,
+            None => sheet.rows.max(start + 1) - 1,
+        };
+        let mut data: Vec<Vec<String>> = (start..=end)
+            .map(|row| {
+                let mut cols: Vec<String> = (shape.start.col..=shape.end.col)
+                    .map(|col| sheet.cell(row, col).to_string())
+                    .collect();
+                while cols.last().map_or(false, String::is_empty) {
+                    cols.pop();
+                }
+                cols
+            })
+            .collect();
+        while data.last().map_or(false, Vec::is_empty) {
+            data.pop();
+        }
+
+        let mut shape = shape.clone();
+        shape.start.row = Some(start);
+        shape.end.row = Some(end);
+        Ok(Matrix { shape, data })
+    }
+
+    pub(crate) fn write(&self, spreadsheet_id: &str, matrix: Matrix) -> Result<()> {
+        self.connect()?;
+        self.write_unchecked(spreadsheet_id, matrix);
+        Ok(())
+    }
+
+    fn write_unchecked(&self, spreadsheet_id: &str, matrix: Matrix) {
+        let mut spreadsheets = self.spreadsheets.lock().unwrap();
+        let sheet = Self::sheet_mut(&mut spreadsheets, spreadsheet_id, &matrix.shape.sheet);
+
+        let start = matrix.shape.start;
+        for (row, cols) in matrix.data.into_iter().enumerate() {
+            for (col, value) in cols.into_iter().enumerate() {
+                let row = start.row.unwrap_or_default() + row as u32;
+                let col = start.col + col as u16;
+                *sheet.cell_mut(row, col) = value;
+            }
+        }
+    }
+
+    /// 주어진 범위에서 시작하는 표의 마지막 행 아래에 행들을 추가합니다.
+    pub(crate) fn append(
+        &self,
+        spreadsheet_id: &str,
+        range: &str,
+        data: Vec<Vec<String>>,
+    ) -> Result<()> {
+        self.connect()?;
+        let mut shape: MatrixShape = range.parse()?;
+        let next = {
+            let mut spreadsheets = self.spreadsheets.lock().unwrap();
+            let sheet = Self::sheet_mut(&mut spreadsheets, spreadsheet_id, &shape.sheet);
+            let start = shape.start.row.unwrap_or_default();
+            (start..sheet.cells.len().max(start as usize) as u32)
+                .rev()
+                .find(|&row| {
+                    (shape.start.col..=shape.end.col).any(|col| !sheet.cell(row, col).is_empty())
+                })
+                .map_or(start, |row| row + 1)
+        };
+
+        shape.start.row = Some(next);
+        self.write_unchecked(spreadsheet_id, Matrix { shape, data });
+        Ok(())
+    }
+
+    fn sheet_mut<'s>(
+        spreadsheets: &'s mut BTreeMap<String, Vec<MemorySheet>>,
+        spreadsheet_id: &str,
+        title: &str,
+    ) -> &'s mut MemorySheet {
+        let title = title.trim_matches('\'');
+        let sheets = spreadsheets.entry(spreadsheet_id.to_string()).or_default();
+        match sheets.iter().position(|sheet| sheet.title == title) {
+            Some(index) => &mut sheets[index],
+            None => {
+                sheets.push(MemorySheet {
+                    title: title.to_string(),
+                    ..Default::default()
+                });
+                sheets.last_mut().unwrap()
+            }
+        }
+    }
+}
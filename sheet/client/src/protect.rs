@@ -0,0 +1,188 @@
+use anyhow::Result;
+use google_sheets4::api::{
+    AddProtectedRangeRequest, DeleteProtectedRangeRequest, Editors, GridRange, ProtectedRange,
+    Request,
+};
+
+#[cfg(feature = "testing")]
+use crate::Backend;
+use crate::{MatrixShape, Spreadsheet, Table};
+
+/// worker가 관리하는 보호 범위의 설명에 붙는 접두사입니다.
+const PROTECTION_PREFIX: &str = "smartx-whitedog:";
+
+/// 테이블의 보호 범위 설정입니다.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Protection {
+    /// 보호할 필드 목록
+    pub fields: Vec<String>,
+    /// 필드 행을 보호할지 여부
+    pub fields_row: bool,
+    /// 수정을 막지 않고, 수정할 때 경고만 표시할지 여부
+    pub warning_only: bool,
+}
+
+impl Spreadsheet {
+    /// 주어진 범위를 보호하고, 보호 범위의 ID를 반환합니다.
+    ///
+    /// `warning_only`가 아니라면 service account만 수정할 수 있습니다.
+    pub async fn protect(&self, range: &str, warning_only: bool) -> Result<i32> {
+        let shape: MatrixShape = range.parse()?;
+        let sheet_id = self.sheet_id(&shape.sheet).await?;
+        let request = self.add_protection(sheet_id, &shape, range.to_string(), warning_only);
+
+        self.batch_update(vec![request])
+            .await?
+            .into_iter()
+            .find_map(|response| response.add_protected_range?.protected_range)
+            .and_then(|protected_range| protected_range.protected_range_id)
+            .ok_or_else(|| anyhow!("cannot protect the range: {}", range))
+    }
+
+    /// 주어진 ID의 보호 범위를 해제합니다.
+    pub async fn unprotect(&self, protected_range_id: i32) -> Result<()> {
+        self.batch_update(vec![Self::delete_protection(protected_range_id)])
+            .await?;
+        Ok(())
+    }
+
+    /// worker가 관리하는 보호 범위 중, 설명이 주어진 문자열로 시작하는 것들을 불러옵니다.
+    async fn search_protections(&self, sheet_id: i32, prefix: &str) -> Result<Vec<ProtectedRange>> {
+        // 메모리에 저장되는 문서는 보호 범위를 저장하지 않습니다.
+        #[cfg(feature = "testing")]
+        if let Backend::Memory(_) = &self.backend {
+            return Ok(vec![]);
+        }
+
+        let (_, ret) = self
+            .hub()?
+            .spreadsheets()
+            .get(&self.id)
+            .param(
+                "fields",
+                "sheets(properties.sheetId,protectedRanges(protectedRangeId,description))",
+            )
+            .doit()
+            .await?;
+
+        let prefix = format!("{}{}", PROTECTION_PREFIX, prefix);
+        Ok(ret
+            .sheets
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|sheet| sheet.properties.as_ref().and_then(|e| e.sheet_id) == Some(sheet_id))
+            .flat_map(|sheet| sheet.protected_ranges.unwrap_or_default())
+            .filter(|protected_range| {
+                protected_range
+                    .description
+                    .as_deref()
+                    .map_or(false, |e| e.starts_with(&prefix))
+            })
+            .collect())
+    }
+
+    fn add_protection(
+        &self,
+        sheet_id: i32,
+        shape: &MatrixShape,
+        description: String,
+        warning_only: bool,
+    ) -> Request {
+        let editors = if warning_only {
+            None
+        } else {
+            Some(Editors {
+                users: Some(vec![self.email.clone()]),
+                ..Default::default()
+            })
+        };
+
+        Request {
+            add_protected_range: Some(AddProtectedRangeRequest {
+                protected_range: Some(ProtectedRange {
+                    range: Some(GridRange {
+                        sheet_id: Some(sheet_id),
+                        start_row_index: shape.start.row.map(|row| row as i32),
+                        end_row_index: shape.end.row.map(|row| row as i32 + 1),
+                        start_column_index: Some(shape.start.col as i32),
+                        end_column_index: Some(shape.end.col as i32 + 1),
+                    }),
+                    description: Some(format!("{}{}", PROTECTION_PREFIX, description)),
+                    warning_only: Some(warning_only),
+                    editors,
+                    ..Default::default()
+                }),
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn delete_protection(protected_range_id: i32) -> Request {
+        Request {
+            delete_protected_range: Some(DeleteProtectedRangeRequest {
+                protected_range_id: Some(protected_range_id),
+            }),
+            ..Default::default()
+        }
+    }
+}
+
+impl<'a, Field> Table<'a, Field> {
+    /// 테이블의 보호 범위를 설정합니다.
+    ///
+    /// 설정한 보호 범위는 `sync_protections`로 반영하며, `revalidate`에서 필드 구조가 바뀌면 다시 반영합니다.
+    pub fn with_protection(mut self, protection: Protection) -> Self {
+        self.protection = Some(protection);
+        self
+    }
+
+    /// 설정한 보호 범위를 현재 필드 구조에 맞게 반영합니다.
+    ///
+    /// 이 테이블에 대해 이전에 반영한 보호 범위들은 모두 해제한 후 다시 추가하며, 하나의 요청으로 처리합니다.
+    pub async fn sync_protections(&self) -> Result<()> {
+        let protection = match &self.protection {
+            Some(protection) => protection,
+            None => return Ok(()),
+        };
+
+        let sheet_id = self.spreadsheet.sheet_id(&self.fields_shape.sheet).await?;
+        let prefix = format!("{}:", &self.fields_shape);
+        let mut requests: Vec<_> = self
+            .spreadsheet
+            .search_protections(sheet_id, &prefix)
+            .await?
+            .into_iter()
+            .filter_map(|protected_range| protected_range.protected_range_id)
+            .map(Spreadsheet::delete_protection)
+            .collect();
+
+        if protection.fields_row {
+            requests.push(self.spreadsheet.add_protection(
+                sheet_id,
+                &self.fields_shape,
+                format!("{}fields", &prefix),
+                protection.warning_only,
+            ));
+        }
+        for name in &protection.fields {
+            let field = self
+                .fields
+                .iter()
+                .find(|e| &e.field == name)
+                .ok_or_else(|| anyhow!("no such field on \"{}\": {}", &self.fields_shape, name))?;
+
+            let mut shape = self.values_shape(0, None);
+            shape.start.col += field.col;
+            shape.end.col = shape.start.col;
+            requests.push(self.spreadsheet.add_protection(
+                sheet_id,
+                &shape,
+                format!("{}{}", &prefix, &field.field),
+                protection.warning_only,
+            ));
+        }
+
+        self.spreadsheet.batch_update(requests).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,110 @@
+use std::ops::{Deref, DerefMut};
+
+use anyhow::Result;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{Table, WriteStatus};
+
+/// 테이블의 행과 그 위치입니다.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Row<Field> {
+    /// 행 번호 (테이블 기준 offset)
+    pub index: u32,
+    /// 행의 값
+    pub data: Field,
+}
+
+impl<Field> Deref for Row<Field> {
+    type Target = Field;
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+impl<Field> DerefMut for Row<Field> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.data
+    }
+}
+
+impl<'a, Field> Table<'a, Field> {
+    /// 주어진 필드의 값이 일치하는 행들을 불러옵니다.
+    ///
+    /// 전체 테이블을 읽지 않고, 해당 필드의 열만 읽어 일치하는 행을 찾은 후 해당 행들만 불러옵니다.
+    pub async fn find_by<T>(&self, field: &str, value: &T) -> Result<Vec<Row<Field>>>
+    where
+        Field: DeserializeOwned,
+        T: Serialize,
+    {
+        let key = self
+            .fields
+            .iter()
+            .find(|e| e.field == field)
+            .ok_or_else(|| anyhow!("no such field on \"{}\": {}", &self.fields_shape, field))?;
+        let value = serde_json::to_value(value)?;
+
+        let mut shape = self.values_shape(0, None);
+        shape.start.col += key.col;
+        shape.end.col = shape.start.col;
+
+        let format = self.format_of(key);
+        let indices: Vec<_> = self
+            .spreadsheet
+            .get(&shape.to_string())
+            .await?
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, mut cols)| {
+                let token = cols.pop().unwrap_or_default();
+                match format.parse(key, token) {
+                    Ok(token) if token == value => Some(index as u32),
+                    _ => None,
+                }
+            })
+            .collect();
+        self.get_rows_at(&indices).await
+    }
+
+    /// 주어진 위치의 행들을 한 번에 불러옵니다.
+    pub async fn get_rows_at(&self, indices: &[u32]) -> Result<Vec<Row<Field>>>
+    where
+        Field: DeserializeOwned,
+    {
+        let ranges: Vec<_> = indices
+            .iter()
+            .map(|index| self.values_shape(*index, Some(*index)).to_string())
+            .collect();
+
+        let matrices = self.spreadsheet.get_many(&ranges).await?;
+        let links = self.get_links(&ranges).await?;
+
+        matrices
+            .into_iter()
+            .zip(indices)
+            .enumerate()
+            .map(|(at, (matrix, index))| {
+                let cols = matrix.into_iter().next().unwrap_or_default();
+                let urls = links
+                    .get(at)
+                    .and_then(|rows| rows.first())
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                let fields = self.parse_values(cols, urls)?;
+                Ok(Row {
+                    index: *index,
+                    data: serde_json::from_value(serde_json::to_value(fields)?)?,
+                })
+            })
+            .collect()
+    }
+
+    /// 주어진 행을 반영합니다.
+    pub async fn set_row(&self, row: &Row<Field>) -> Result<WriteStatus>
+    where
+        Field: Serialize,
+    {
+        self.set_rows(std::slice::from_ref(&row.data), row.index)
+            .await
+    }
+}
@@ -0,0 +1,195 @@
+use anyhow::Result;
+use google_sheets4::api::{
+    CreateDeveloperMetadataRequest, DataFilter, DeveloperMetadata, DeveloperMetadataLocation,
+    DeveloperMetadataLookup, DimensionRange, Request, SearchDeveloperMetadataRequest,
+    UpdateDeveloperMetadataRequest,
+};
+use serde::de::DeserializeOwned;
+
+use crate::Table;
+
+/// 행에 숨겨서 저장하는 식별 정보입니다.
+///
+/// Google Sheets의 developer metadata로 저장되므로, 사용자가 행을 정렬하거나 중간에 행을 추가하더라도 행과 함께 이동합니다.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RowTag {
+    /// 레코드의 고유 ID
+    pub id: String,
+    /// 마지막으로 동기화된 행의 해시값
+    pub hash: Option<String>,
+}
+
+/// 식별 정보가 붙은 행입니다.
+#[derive(Clone, Debug)]
+pub struct Tagged<Field> {
+    /// 행 번호 (테이블 기준 offset)
+    pub row: u32,
+    /// 식별 정보
+    pub tag: RowTag,
+    /// 행의 값
+    pub data: Field,
+}
+
+impl<'a, Field> Table<'a, Field> {
+    const TAG_KEY: &'static str = "smartx-whitedog-row";
+
+    /// 테이블의 모든 행 식별 정보를 불러옵니다.
+    pub async fn get_row_tags(&self) -> Result<Vec<(u32, RowTag)>> {
+        let sheet_id = self.spreadsheet.sheet_id(&self.fields_shape.sheet).await?;
+        Ok(self
+            .search_row_tags(sheet_id)
+            .await?
+            .into_iter()
+            .map(|(_, row, tag)| (row, tag))
+            .collect())
+    }
+
+    /// 주어진 행들에 식별 정보를 저장합니다.
+    ///
+    /// 이미 식별 정보가 있는 행은 새로운 값으로 덮어씁니다.
+    pub async fn set_row_tags(&self, tags: &[(u32, RowTag)]) -> Result<()> {
+        let sheet_id = self.spreadsheet.sheet_id(&self.fields_shape.sheet).await?;
+        let existing = self.search_row_tags(sheet_id).await?;
+
+        let requests = tags
+            .iter()
+            .map(|(row, tag)| {
+                let value = serde_json::to_string(tag)?;
+                let request = match existing.iter().find(|(_, e, _)| e == row) {
+                    Some((metadata_id, _, _)) => Request {
+                        update_developer_metadata: Some(UpdateDeveloperMetadataRequest {
+                            data_filters: Some(vec![DataFilter {
+                                developer_metadata_lookup: Some(DeveloperMetadataLookup {
+                                    metadata_id: Some(*metadata_id),
+                                    ..Default::default()
+                                }),
+                                ..Default::default()
+                            }]),
+                            developer_metadata: Some(DeveloperMetadata {
+                                metadata_value: Some(value),
+                                ..Default::default()
+                            }),
+                            fields: Some("metadataValue".to_string()),
+                        }),
+                        ..Default::default()
+                    },
+                    None => {
+                        let row = self.values_row(*row) as i32;
+                        Request {
+                            create_developer_metadata: Some(CreateDeveloperMetadataRequest {
+                                developer_metadata: Some(DeveloperMetadata {
+                                    metadata_key: Some(Self::TAG_KEY.to_string()),
+                                    metadata_value: Some(value),
+                                    visibility: Some("PROJECT".to_string()),
+                                    location: Some(DeveloperMetadataLocation {
+                                        dimension_range: Some(DimensionRange {
+                                            sheet_id: Some(sheet_id),
+                                            dimension: Some("ROWS".to_string()),
+                                            start_index: Some(row),
+                                            end_index: Some(row + 1),
+                                        }),
+                                        ..Default::default()
+                                    }),
+                                    ..Default::default()
+                                }),
+                            }),
+                            ..Default::default()
+                        }
+                    }
+                };
+                Ok(request)
+            })
+            .collect::<Result<_>>()?;
+        self.spreadsheet.batch_update(requests).await?;
+        Ok(())
+    }
+
+    /// 식별 정보의 ID로 행 번호를 찾습니다.
+    pub async fn find_row_by_tag(&self, id: &str) -> Result<Option<u32>> {
+        Ok(self
+            .get_row_tags()
+            .await?
+            .into_iter()
+            .find(|(_, tag)| tag.id == id)
+            .map(|(row, _)| row))
+    }
+
+    /// 식별 정보가 붙은 모든 행을 불러옵니다.
+    pub async fn get_tagged_rows(&self) -> Result<Vec<Tagged<Field>>>
+    where
+        Field: DeserializeOwned,
+    {
+        let mut tags = self.get_row_tags().await?;
+        let length = match tags.iter().map(|(row, _)| *row).max() {
+            Some(row) => row + 1,
+            None => return Ok(vec![]),
+        };
+        let mut rows: Vec<_> = self
+            .get_rows(Some(length))
+            .await?
+            .into_iter()
+            .map(Some)
+            .collect();
+
+        tags.sort_by_key(|(row, _)| *row);
+        Ok(tags
+            .into_iter()
+            .filter_map(|(row, tag)| {
+                rows.get_mut(row as usize)
+                    .and_then(Option::take)
+                    .map(|data| Tagged { row, tag, data })
+            })
+            .collect())
+    }
+
+    async fn search_row_tags(&self, sheet_id: i32) -> Result<Vec<(i32, u32, RowTag)>> {
+        let request = SearchDeveloperMetadataRequest {
+            data_filters: Some(vec![DataFilter {
+                developer_metadata_lookup: Some(DeveloperMetadataLookup {
+                    metadata_key: Some(Self::TAG_KEY.to_string()),
+                    location_type: Some("ROW".to_string()),
+                    location_matching_strategy: Some("INTERSECTING_LOCATION".to_string()),
+                    metadata_location: Some(DeveloperMetadataLocation {
+                        dimension_range: Some(DimensionRange {
+                            sheet_id: Some(sheet_id),
+                            dimension: Some("ROWS".to_string()),
+                            start_index: Some(self.values_row(0) as i32),
+                            end_index: None,
+                        }),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }]),
+        };
+        let (_, ret) = self
+            .spreadsheet
+            .hub()?
+            .spreadsheets()
+            .developer_metadata_search(request, &self.spreadsheet.id)
+            .doit()
+            .await?;
+
+        let row_start = self.values_row(0);
+        ret.matched_developer_metadata
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|matched| matched.developer_metadata)
+            .filter_map(|metadata| {
+                let row = metadata
+                    .location?
+                    .dimension_range?
+                    .start_index
+                    .map(|row| row as u32)
+                    .filter(|row| *row >= row_start)?;
+                Some((
+                    metadata.metadata_id?,
+                    row - row_start,
+                    metadata.metadata_value?,
+                ))
+            })
+            .map(|(metadata_id, row, value)| Ok((metadata_id, row, serde_json::from_str(&value)?)))
+            .collect()
+    }
+}
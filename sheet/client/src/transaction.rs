@@ -0,0 +1,123 @@
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::{Matrix, Spreadsheet, Table};
+
+/// 여러 범위에 대한 쓰기 요청을 하나로 묶어 반영합니다.
+///
+/// 반영하기 전에 모든 범위의 기존 값을 저장해두며, 도중에 실패하면 이미 반영한 범위를 기존 값으로 되돌립니다.
+/// 같은 문서에 대한 요청들은 한 번의 batchUpdate로 반영합니다.
+///
+/// ## Note
+/// 트랜잭션은 로컬 저장소의 쓰기 대기열을 사용하지 않습니다.
+/// 문서에 접근할 수 없다면 아무 값도 반영하지 않고 실패합니다.
+#[derive(Clone, Default)]
+pub struct Transaction {
+    writes: Vec<(Spreadsheet, Vec<Matrix>)>,
+}
+
+impl Spreadsheet {
+    /// 이 문서에 대한 새로운 트랜잭션을 시작합니다.
+    ///
+    /// 트랜잭션에는 다른 문서에 대한 요청도 함께 추가할 수 있습니다.
+    pub fn transaction(&self) -> Transaction {
+        Transaction {
+            writes: vec![(self.clone(), vec![])],
+        }
+    }
+}
+
+impl Transaction {
+    /// 주어진 범위에 값을 쓰는 요청을 추가합니다.
+    pub fn update(
+        &mut self,
+        spreadsheet: &Spreadsheet,
+        range: &str,
+        data: Vec<Vec<String>>,
+    ) -> Result<()> {
+        let matrix = Matrix {
+            shape: range.parse()?,
+            data,
+        };
+        self.push(spreadsheet, vec![matrix]);
+        Ok(())
+    }
+
+    /// 테이블 객체를 반영하는 요청을 추가합니다.
+    pub fn set_rows<Field>(
+        &mut self,
+        table: &Table<'_, Field>,
+        rows: &[Field],
+        offset: u32,
+    ) -> Result<()>
+    where
+        Field: Serialize,
+    {
+        let matrices = table.render_rows(rows, offset)?;
+        self.push(table.spreadsheet(), matrices);
+        Ok(())
+    }
+
+    /// 반영할 요청이 없는지 여부를 반환합니다.
+    pub fn is_empty(&self) -> bool {
+        self.writes.iter().all(|(_, matrices)| matrices.is_empty())
+    }
+
+    /// 모든 요청을 반영합니다.
+    ///
+    /// 도중에 실패하면 이미 반영한 범위를 기존 값으로 되돌린 후 오류를 반환합니다.
+    pub async fn commit(self) -> Result<()> {
+        // 수식을 보존하기 위해, 기존 값은 입력된 그대로 저장합니다.
+        let mut backups = vec![];
+        for (spreadsheet, matrices) in &self.writes {
+            if matrices.is_empty() {
+                backups.push(vec![]);
+                continue;
+            }
+
+            let ranges: Vec<_> = matrices.iter().map(|e| e.shape.to_string()).collect();
+            let prior = spreadsheet
+                .get_entered_many(&ranges)
+                .await?
+                .into_iter()
+                .zip(matrices)
+                .map(|(prior, matrix)| Matrix {
+                    shape: matrix.shape.clone(),
+                    data: Matrix {
+                        shape: matrix.shape.clone(),
+                        data: prior,
+                    }
+                    .into_iter()
+                    .collect(),
+                })
+                .collect::<Vec<_>>();
+            backups.push(prior);
+        }
+
+        for (index, (spreadsheet, matrices)) in self.writes.iter().enumerate() {
+            if let Err(error) = spreadsheet.update_many_remote(matrices.clone()).await {
+                // 실패한 요청도 일부 반영되었을 수 있으므로, 함께 되돌립니다.
+                for ((spreadsheet, _), prior) in self.writes[..=index].iter().zip(&backups).rev() {
+                    if let Err(rollback_error) = spreadsheet.update_many_remote(prior.clone()).await
+                    {
+                        bail!(
+                            "failed to rollback the transaction on {}: {} (caused by: {})",
+                            &spreadsheet.id,
+                            rollback_error,
+                            error,
+                        );
+                    }
+                }
+                bail!("the transaction has been rolled back: {}", error);
+            }
+        }
+        Ok(())
+    }
+
+    fn push(&mut self, spreadsheet: &Spreadsheet, mut matrices: Vec<Matrix>) {
+        match self.writes.iter_mut().find(|(e, _)| e.id == spreadsheet.id) {
+            Some((_, writes)) => writes.append(&mut matrices),
+            None => self.writes.push((spreadsheet.clone(), matrices)),
+        }
+    }
+}
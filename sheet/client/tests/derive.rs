@@ -0,0 +1,89 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use smartx_whitedog_sheet_client::{Column, SheetTable};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema, SheetTable)]
+#[serde(rename_all = "camelCase")]
+#[sheet(range = "Purchase!A1:Z1", key = "purchaseId")]
+struct Purchase {
+    purchase_id: String,
+    #[sheet(label = "품목", read_only)]
+    item_name: Option<String>,
+    #[serde(rename = "state")]
+    #[sheet(read_only)]
+    status: Option<String>,
+}
+
+#[test]
+fn test_derive_rename_all() {
+    assert_eq!(Purchase::NAME, "purchase");
+    assert_eq!(Purchase::RANGE, "Purchase!A1:Z1");
+    assert_eq!(Purchase::KEY, Some("purchaseId"));
+    assert_eq!(
+        Purchase::columns(),
+        vec![
+            Column {
+                field: "itemName".to_string(),
+                label: Some("품목".to_string()),
+                read_only: true,
+                choices: vec![],
+            },
+            Column {
+                field: "state".to_string(),
+                label: None,
+                read_only: true,
+                choices: vec![],
+            },
+        ],
+    );
+}
+
+#[test]
+fn test_derive_columns_match_schema() {
+    let schema = schemars::schema_for!(Purchase).schema;
+    let properties = schema.object.unwrap().properties;
+    for column in Purchase::columns() {
+        assert!(properties.contains_key(&column.field), "{}", &column.field);
+    }
+    assert!(properties.contains_key(Purchase::KEY.unwrap()));
+}
+
+#[tokio::test]
+async fn test_open_renamed_table() -> anyhow::Result<()> {
+    use smartx_whitedog_sheet_client::{MemorySheets, SheetClient};
+
+    let sheets = MemorySheets::default();
+    sheets.set(
+        "spreadsheet",
+        "Purchase!A1:C2",
+        vec![
+            vec![
+                "purchaseId".to_string(),
+                "품목".to_string(),
+                "state".to_string(),
+            ],
+            vec!["p1".to_string(), "사과".to_string(), "paid".to_string()],
+        ],
+    )?;
+    let spreadsheet = SheetClient::memory(sheets.clone()).into_sheet_unchecked("spreadsheet");
+
+    // serde로 바꾼 이름이 표시된 열도 필드로 읽습니다.
+    let table = spreadsheet.open_table::<Purchase>().await?;
+    let purchase = Purchase {
+        purchase_id: "p1".to_string(),
+        item_name: Some("사과".to_string()),
+        status: Some("paid".to_string()),
+    };
+    assert_eq!(table.get_rows(None).await?, vec![purchase.clone()]);
+
+    let purchase = Purchase {
+        purchase_id: "p2".to_string(),
+        ..purchase
+    };
+    table.set_rows(&[purchase], 1).await?;
+    assert_eq!(
+        sheets.get("spreadsheet", "Purchase!A3:A3")?,
+        vec![vec!["p2".to_string()]],
+    );
+    Ok(())
+}
@@ -0,0 +1,119 @@
+use std::{env, fs, path::PathBuf};
+
+use anyhow::Result;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use smartx_whitedog_sheet_client::{
+    ConflictPolicy, MemorySheets, SheetClient, Spreadsheet, WriteStatus,
+};
+
+const ID: &str = "spreadsheet";
+const FIELDS: &str = "Items!A1:B1";
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+struct Item {
+    name: Option<String>,
+    note: Option<String>,
+}
+
+fn item(name: &str) -> Item {
+    Item {
+        name: Some(name.to_string()),
+        note: None,
+    }
+}
+
+/// 테스트마다 비어있는 로컬 저장소를 사용합니다.
+fn cache_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("sheet-client-{}-{}", name, std::process::id()));
+    fs::remove_dir_all(&dir).ok();
+    dir
+}
+
+fn open(sheets: &MemorySheets, cache: &str) -> Result<Spreadsheet> {
+    sheets.set(
+        ID,
+        FIELDS,
+        vec![vec!["name".to_string(), "note".to_string()]],
+    )?;
+    SheetClient::memory(sheets.clone())
+        .into_sheet_unchecked(ID)
+        .with_cache(cache_dir(cache))
+}
+
+#[tokio::test]
+async fn test_queue_writes_while_offline() -> Result<()> {
+    let sheets = MemorySheets::default();
+    let spreadsheet = open(&sheets, "queue")?;
+    let table = spreadsheet.get_table::<Item>(FIELDS).await?;
+    assert_eq!(table.get_filled_rows(None).await?, vec![]);
+
+    sheets.set_offline(true);
+    assert_eq!(table.set_rows(&[item("a")], 0).await?, WriteStatus::Queued);
+    assert_eq!(sheets.get(ID, "Items!A2:B")?, Vec::<Vec<String>>::new());
+
+    // 연결이 끊긴 동안에는 저장된 읽기 결과를 반환합니다.
+    let snapshot = table.get_rows_snapshot(None).await?;
+    assert!(snapshot.is_stale());
+    assert!(snapshot.data.iter().all(|row| row == &Item::default()));
+
+    sheets.set_offline(false);
+    assert_eq!(spreadsheet.replay_pending().await?, 1);
+    assert_eq!(table.get_rows(None).await?, vec![item("a")]);
+    assert_eq!(table.set_rows(&[item("b")], 1).await?, WriteStatus::Written);
+    assert_eq!(table.get_rows(None).await?, vec![item("a"), item("b")]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_skip_checked_writes_while_offline() -> Result<()> {
+    let sheets = MemorySheets::default();
+    let spreadsheet = open(&sheets, "checked")?;
+    let table = spreadsheet.get_table::<Item>(FIELDS).await?;
+    table.set_rows(&[item("a")], 0).await?;
+    let base = table.get_rows(Some(1)).await?;
+
+    // 연결이 끊긴 동안 사람이 수정한 값은 저장된 읽기 결과에 없습니다.
+    sheets.set(ID, "Items!B2", vec![vec!["human".to_string()]])?;
+    sheets.set_offline(true);
+    let ours = vec![item("b")];
+    let written = table
+        .set_rows_checked(&base, &ours, 0, ConflictPolicy::Merge)
+        .await?;
+    assert_eq!(written.status, Some(WriteStatus::Offline));
+    assert!(written.conflicts.is_empty());
+
+    // 대기열에 저장하지 않으므로, 연결이 복구된 후 현재 문서와 다시 비교하여 반영합니다.
+    sheets.set_offline(false);
+    assert_eq!(spreadsheet.replay_pending().await?, 0);
+    let written = table
+        .set_rows_checked(&base, &ours, 0, ConflictPolicy::Merge)
+        .await?;
+    assert_eq!(written.status, Some(WriteStatus::Written));
+    assert_eq!(
+        table.get_rows(Some(1)).await?,
+        vec![Item {
+            name: Some("b".to_string()),
+            note: Some("human".to_string()),
+        }],
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_record_batch_update_requests() -> Result<()> {
+    let sheets = MemorySheets::default();
+    let spreadsheet = open(&sheets, "requests")?;
+
+    spreadsheet.delete_sheet("Items").await?;
+    let requests = sheets.requests(ID);
+    assert_eq!(requests.len(), 1);
+    assert_eq!(
+        requests[0]
+            .delete_sheet
+            .as_ref()
+            .and_then(|request| request.sheet_id),
+        Some(0),
+    );
+    Ok(())
+}
@@ -0,0 +1,145 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::PathBuf,
+};
+
+use anyhow::Result;
+use serde_json::Value;
+use smartx_whitedog_common::models::chrono::DateTime;
+use smartx_whitedog_sheet_client::{Spreadsheet, Table};
+use tokio::sync::Mutex;
+
+/// worker가 수정한 셀 하나의 기록입니다.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct AuditEntry {
+    /// 수정한 시각 (RFC 3339)
+    pub timestamp: String,
+    /// 테이블 이름
+    pub table: String,
+    /// 행의 키 (키 필드가 없다면 테이블 기준 행 번호)
+    pub row_key: String,
+    /// 필드 이름
+    pub field: String,
+    /// 수정하기 전의 값 (JSON)
+    pub old_value: String,
+    /// 수정한 후의 값 (JSON)
+    pub new_value: String,
+    /// 수정한 이유 (controller)
+    pub reason: String,
+}
+
+impl AuditEntry {
+    pub(crate) fn new(
+        now: DateTime,
+        table: &str,
+        row_key: String,
+        field: &str,
+        old_value: &Value,
+        new_value: &Value,
+        reason: &str,
+    ) -> Self {
+        Self {
+            timestamp: now.0.to_rfc3339(),
+            table: table.to_string(),
+            row_key,
+            field: field.to_string(),
+            old_value: old_value.to_string(),
+            new_value: new_value.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    fn to_row(&self) -> Vec<String> {
+        vec![
+            self.timestamp.clone(),
+            self.table.clone(),
+            self.row_key.clone(),
+            self.field.clone(),
+            self.old_value.clone(),
+            self.new_value.clone(),
+            self.reason.clone(),
+        ]
+    }
+}
+
+/// worker가 수정한 셀들을 기록하는 추가 전용 감사 기록입니다.
+///
+/// 관리 문서의 감사 시트에 기록하며, 로컬 파일이 주어진 경우 JSON Lines 형식으로 함께 기록합니다.
+/// 감사 시트의 첫 행에는 `AuditEntry`의 필드 이름들이 순서대로 있어야 합니다.
+pub struct AuditLog {
+    range: Option<String>,
+    file: Option<PathBuf>,
+    lock: Mutex<()>,
+}
+
+impl AuditLog {
+    pub fn new(range: Option<String>, file: Option<PathBuf>) -> Self {
+        Self {
+            range,
+            file,
+            lock: Default::default(),
+        }
+    }
+
+    /// 감사 기록을 추가합니다.
+    pub async fn record(&self, spreadsheet: &Spreadsheet, entries: Vec<AuditEntry>) -> Result<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let _lock = self.lock.lock().await;
+
+        if let Some(path) = &self.file {
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            for entry in &entries {
+                writeln!(file, "{}", serde_json::to_string(entry)?)?;
+            }
+            file.sync_data()?;
+        }
+        if let Some(range) = &self.range {
+            let data = entries.iter().map(AuditEntry::to_row).collect();
+            spreadsheet.append(range, data).await?;
+        }
+        Ok(())
+    }
+
+    /// 주어진 행의 수정 기록을 오래된 순서로 불러옵니다.
+    ///
+    /// 로컬 파일이 주어졌다면 파일에서, 그렇지 않다면 감사 시트에서 불러옵니다.
+    pub async fn history(
+        &self,
+        spreadsheet: &Spreadsheet,
+        table: &str,
+        row_key: &str,
+    ) -> Result<Vec<AuditEntry>> {
+        let entries = match (&self.file, &self.range) {
+            (Some(path), _) => {
+                let _lock = self.lock.lock().await;
+                if !path.exists() {
+                    return Ok(vec![]);
+                }
+                BufReader::new(File::open(path)?)
+                    .lines()
+                    .filter(|line| !matches!(line, Ok(line) if line.trim().is_empty()))
+                    .map(|line| Ok(serde_json::from_str(&line?)?))
+                    .collect::<Result<Vec<AuditEntry>>>()?
+            }
+            (None, Some(range)) => {
+                let audit: Table<AuditEntry> = spreadsheet.get_table(range).await?;
+                // 감사 시트의 남은 빈 행들은 읽지 않습니다.
+                audit
+                    .get_filled_rows(None)
+                    .await?
+                    .into_iter()
+                    .map(|row| row.data)
+                    .collect()
+            }
+            (None, None) => bail!("the audit log is disabled"),
+        };
+
+        Ok(entries
+            .into_iter()
+            .filter(|entry| entry.table == table && entry.row_key == row_key)
+            .collect())
+    }
+}
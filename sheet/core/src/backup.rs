@@ -0,0 +1,109 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use smartx_whitedog_common::models::chrono::DateTimeFormat;
+use tokio::time::Instant;
+
+use crate::worker::SheetWorker;
+
+/// 백업 시트 이름에서 원본 시트 이름과 시각을 구분하는 문자입니다.
+const BACKUP_SEPARATOR: char = '@';
+
+/// 관리 문서의 백업 정책입니다.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BackupPolicy {
+    /// 백업할 시트 목록
+    pub tabs: Vec<String>,
+    /// 백업 주기
+    pub interval: Duration,
+    /// 시트마다 보관할 최대 백업 수 (오래된 것부터 삭제합니다)
+    pub retention: usize,
+}
+
+impl Default for BackupPolicy {
+    fn default() -> Self {
+        Self {
+            tabs: vec![],
+            interval: Duration::from_secs(60 * 60),
+            retention: 24,
+        }
+    }
+}
+
+impl<R> SheetWorker<R> {
+    /// 백업 주기가 지났다면 백업을 수행하고, 오래된 백업을 삭제합니다.
+    pub(crate) async fn backup_if_due(&self) -> Result<()> {
+        let mut last_backup = self.last_backup.lock().await;
+        if let Some(last_backup) = *last_backup {
+            if last_backup.elapsed() < self.backup.interval {
+                return Ok(());
+            }
+        }
+
+        self.backup().await?;
+        self.prune_backups().await?;
+        *last_backup = Some(Instant::now());
+        Ok(())
+    }
+
+    /// 관리 중인 시트들을 백업 문서에 `<시트 이름>@<시각>` 이름으로 복사하고, 생성한 시트 이름들을 반환합니다.
+    pub async fn backup(&self) -> Result<Vec<String>> {
+        let timestamp = self.now()?.format(DateTimeFormat::YYYYMMDDHHMMSS);
+
+        let mut snapshots = vec![];
+        for tab in &self.backup.tabs {
+            let snapshot = format!("{}{}{}", tab, BACKUP_SEPARATOR, &timestamp);
+            self.spreadsheet_management
+                .copy_sheet_to(tab, &self.spreadsheet_backup, &snapshot)
+                .await?;
+            snapshots.push(snapshot);
+        }
+        info!("backed up {} sheet(s)", snapshots.len());
+        Ok(snapshots)
+    }
+
+    /// 주어진 시트의 백업 목록을 오래된 순서로 불러옵니다.
+    pub async fn list_backups(&self, tab: &str) -> Result<Vec<String>> {
+        let prefix = format!("{}{}", tab, BACKUP_SEPARATOR);
+        let mut snapshots: Vec<_> = self
+            .spreadsheet_backup
+            .sheet_titles()
+            .await?
+            .into_iter()
+            .filter(|title| title.starts_with(&prefix))
+            .collect();
+        snapshots.sort();
+        Ok(snapshots)
+    }
+
+    /// 보관 정책을 초과한 오래된 백업을 삭제하고, 삭제한 백업의 수를 반환합니다.
+    pub async fn prune_backups(&self) -> Result<usize> {
+        let mut count = 0;
+        for tab in &self.backup.tabs {
+            let snapshots = self.list_backups(tab).await?;
+            let num_expired = snapshots.len().saturating_sub(self.backup.retention);
+            for snapshot in &snapshots[..num_expired] {
+                self.spreadsheet_backup.delete_sheet(snapshot).await?;
+                count += 1;
+            }
+        }
+        if count > 0 {
+            info!("pruned {} backup(s)", count);
+        }
+        Ok(count)
+    }
+
+    /// 주어진 백업을 원본 시트에 덮어씁니다.
+    ///
+    /// 원본 시트는 교체하지 않고 값과 수식만 덮어쓰므로, 행 식별 정보와 보호 범위는 유지됩니다.
+    pub async fn restore(&self, snapshot: &str) -> Result<()> {
+        let (tab, _) = snapshot
+            .rsplit_once(BACKUP_SEPARATOR)
+            .ok_or_else(|| anyhow!("malformed backup name: {}", snapshot))?;
+        self.spreadsheet_management
+            .copy_values_from(&self.spreadsheet_backup, snapshot, tab)
+            .await?;
+        info!("restored {} from {}", tab, snapshot);
+        Ok(())
+    }
+}
@@ -0,0 +1,413 @@
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use smartx_whitedog_sheet_client::MatrixShape;
+
+use crate::{audit::AuditLog, backup::BackupPolicy, registry::Registry, state::StateStore};
+
+/// `SheetWorker`의 설정입니다.
+///
+/// TOML 파일에서 불러온 후 환경변수로 덮어쓸 수 있습니다.
+///
+/// ```toml
+/// interval = 10
+/// header_range = "Metadata!A1:G1"
+/// errors_range = "Errors!A1:E1"
+///
+/// [spreadsheet]
+/// management_id = "..."
+/// backup_id = "..."
+///
+/// [audit]
+/// range = "Audit!A1:G1"
+/// file = "audit.jsonl"
+///
+/// [dry_run]
+/// enabled = false
+/// range = "DryRun!A1:G1"
+/// file = "dry-run.jsonl"
+///
+/// [state]
+/// file = "sheet-worker-state.json"
+///
+/// [backup]
+/// tabs = ["Metadata", "Purchase"]
+/// interval = 3600
+/// retention = 24
+///
+/// [tables.purchase]
+/// range = "Purchase!A1:Z1"
+/// key = "id"
+///
+/// [tables.purchase.options]
+/// zeus_url = "https://..."
+/// ```
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SheetConfig {
+    pub spreadsheet: SpreadsheetConfig,
+    /// `Header`의 필드 행 범위 (`alert` 열이 없는 이전 문서는 알림을 로그에만 남깁니다)
+    pub header_range: String,
+    /// 오류 기록을 추가할 범위 (시각, 테이블, 행, controller, 오류)
+    pub errors_range: String,
+    /// tick 주기 (초 단위)
+    pub interval: u64,
+    pub audit: AuditConfig,
+    pub dry_run: DryRunConfig,
+    pub state: StateConfig,
+    pub backup: BackupConfig,
+    /// 테이블 이름별 설정
+    pub tables: BTreeMap<String, TableConfig>,
+}
+
+/// 관리할 문서들의 ID입니다.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SpreadsheetConfig {
+    /// 관리하고자 하는 문서 ID
+    pub management_id: String,
+    /// 백업을 위한 문서 ID
+    pub backup_id: String,
+}
+
+/// 감사 기록 설정입니다.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuditConfig {
+    /// 감사 기록을 추가할 범위 (기본값: `None`, 시트에 기록하지 않습니다)
+    pub range: Option<String>,
+    /// 감사 기록을 추가할 로컬 파일 경로 (JSON Lines)
+    pub file: Option<PathBuf>,
+}
+
+/// 드라이런 설정입니다.
+///
+/// 드라이런 중에는 관리 문서를 수정하지 않고, 의도한 변경 사항을 로그와 아래의 범위 및 파일에 기록합니다.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DryRunConfig {
+    /// 드라이런 여부
+    pub enabled: bool,
+    /// 변경 사항을 추가할 범위 (`AuditEntry`와 같은 형식)
+    pub range: Option<String>,
+    /// 변경 사항을 추가할 로컬 파일 경로 (JSON Lines)
+    pub file: Option<PathBuf>,
+}
+
+/// 처리 상태 저장소 설정입니다.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StateConfig {
+    /// 처리 상태를 기록할 로컬 파일 경로 (`None`이라면 재시작하면 상태를 잃습니다)
+    pub file: Option<PathBuf>,
+}
+
+/// 백업 설정입니다.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BackupConfig {
+    /// 백업할 시트 목록 (기본값: `Header`와 등록된 테이블들의 시트)
+    pub tabs: Option<Vec<String>>,
+    /// 백업 주기 (초 단위)
+    pub interval: u64,
+    /// 시트마다 보관할 최대 백업 수
+    pub retention: usize,
+}
+
+/// 테이블의 설정입니다.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TableConfig {
+    /// 필드 행 범위 (기본값: 등록할 때 주어진 범위)
+    pub range: Option<String>,
+    /// 감사 기록과 처리 상태에서 행을 구분하는 필드 (기본값: 행 번호, 확인이 필요한 작업은 필수)
+    pub key: Option<String>,
+    /// controller에 전달할 설정
+    pub options: toml::value::Table,
+}
+
+impl Default for SheetConfig {
+    fn default() -> Self {
+        Self {
+            spreadsheet: Default::default(),
+            header_range: "Metadata!A1:G1".to_string(),
+            errors_range: "Errors!A1:E1".to_string(),
+            interval: 10,
+            audit: Default::default(),
+            dry_run: Default::default(),
+            state: Default::default(),
+            backup: Default::default(),
+            tables: Default::default(),
+        }
+    }
+}
+
+impl Default for StateConfig {
+    fn default() -> Self {
+        Self {
+            file: Some(PathBuf::from("sheet-worker-state.json")),
+        }
+    }
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        let policy = BackupPolicy::default();
+        Self {
+            tabs: None,
+            interval: policy.interval.as_secs(),
+            retention: policy.retention,
+        }
+    }
+}
+
+impl SheetConfig {
+    const DEFAULT_PATH: &'static str = "sheet-worker.toml";
+
+    /// 설정을 불러옵니다.
+    ///
+    /// ## Note
+    /// 설정 파일의 경로는 다음의 환경변수로 지정할 수 있습니다.
+    /// * SHEET_WORKER_CONFIG: 설정 파일 경로 (기본값: `sheet-worker.toml`, 기본 경로에 파일이 없다면 기본 설정을 사용합니다)
+    ///
+    /// 다음의 환경변수가 주어진 경우, 설정 파일의 값을 덮어씁니다.
+    /// * GOOGLE_SPREADSHEET_MANAGEMENT_ID: 관리하고자 하는 문서 ID
+    /// * GOOGLE_SPREADSHEET_BACKUP_ID: 백업을 위한 문서 ID
+    /// * GOOGLE_SPREADSHEET_HEADER_RANGE: `Header`의 필드 행 범위
+    /// * GOOGLE_SPREADSHEET_ERRORS_RANGE: 오류 기록을 추가할 범위
+    /// * GOOGLE_SPREADSHEET_AUDIT_RANGE: 감사 기록을 추가할 범위
+    /// * SHEET_WORKER_AUDIT_FILE: 감사 기록을 추가할 로컬 파일 경로
+    /// * SHEET_WORKER_DRY_RUN: 드라이런 여부 (`true` / `false`)
+    /// * GOOGLE_SPREADSHEET_DRY_RUN_RANGE: 드라이런의 변경 사항을 추가할 범위
+    /// * SHEET_WORKER_DRY_RUN_FILE: 드라이런의 변경 사항을 추가할 로컬 파일 경로
+    /// * SHEET_WORKER_STATE_FILE: 처리 상태를 기록할 로컬 파일 경로
+    /// * GOOGLE_SPREADSHEET_BACKUP_TABS: 백업할 시트 목록 (`,`로 구분)
+    /// * GOOGLE_SPREADSHEET_BACKUP_INTERVAL: 백업 주기 (초 단위)
+    /// * GOOGLE_SPREADSHEET_BACKUP_RETENTION: 시트마다 보관할 최대 백업 수
+    /// * GOOGLE_SPREADSHEET_TABLE_<NAME>_RANGE: 주어진 이름의 테이블의 필드 행 범위 (이름은 대문자)
+    /// * SHEET_WORKER_INTERVAL: tick 주기 (초 단위)
+    pub fn load() -> Result<Self> {
+        let mut config = match env::var("SHEET_WORKER_CONFIG") {
+            Ok(path) => Self::from_file(path)?,
+            Err(_) if Path::new(Self::DEFAULT_PATH).exists() => {
+                Self::from_file(Self::DEFAULT_PATH)?
+            }
+            Err(_) => Self::default(),
+        };
+        config.apply_env()?;
+        Ok(config)
+    }
+
+    /// 주어진 TOML 파일에서 설정을 불러옵니다.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .map_err(|e| anyhow!("cannot read the config file {}: {}", path.display(), e))?;
+        toml::from_str(&text)
+            .map_err(|e| anyhow!("malformed config file {}: {}", path.display(), e))
+    }
+
+    fn apply_env(&mut self) -> Result<()> {
+        fn parse<T>(key: &str) -> Result<Option<T>>
+        where
+            T: FromStr,
+            <T as FromStr>::Err: std::fmt::Display,
+        {
+            match env::var(key) {
+                Ok(value) => value
+                    .trim()
+                    .parse()
+                    .map(Some)
+                    .map_err(|e| anyhow!("malformed environment variable {}: {}", key, e)),
+                Err(_) => Ok(None),
+            }
+        }
+
+        if let Some(id) = parse("GOOGLE_SPREADSHEET_MANAGEMENT_ID")? {
+            self.spreadsheet.management_id = id;
+        }
+        if let Some(id) = parse("GOOGLE_SPREADSHEET_BACKUP_ID")? {
+            self.spreadsheet.backup_id = id;
+        }
+        if let Some(range) = parse("GOOGLE_SPREADSHEET_HEADER_RANGE")? {
+            self.header_range = range;
+        }
+        if let Some(range) = parse("GOOGLE_SPREADSHEET_ERRORS_RANGE")? {
+            self.errors_range = range;
+        }
+        if let Some(range) = parse("GOOGLE_SPREADSHEET_AUDIT_RANGE")? {
+            self.audit.range = Some(range);
+        }
+        if let Some(file) = parse("SHEET_WORKER_AUDIT_FILE")? {
+            self.audit.file = Some(file);
+        }
+        if let Some(enabled) = parse("SHEET_WORKER_DRY_RUN")? {
+            self.dry_run.enabled = enabled;
+        }
+        if let Some(range) = parse("GOOGLE_SPREADSHEET_DRY_RUN_RANGE")? {
+            self.dry_run.range = Some(range);
+        }
+        if let Some(file) = parse("SHEET_WORKER_DRY_RUN_FILE")? {
+            self.dry_run.file = Some(file);
+        }
+        if let Some(file) = parse("SHEET_WORKER_STATE_FILE")? {
+            self.state.file = Some(file);
+        }
+        if let Some(interval) = parse("SHEET_WORKER_INTERVAL")? {
+            self.interval = interval;
+        }
+        if let Some(tabs) = parse::<String>("GOOGLE_SPREADSHEET_BACKUP_TABS")? {
+            self.backup.tabs = Some(
+                tabs.split(',')
+                    .map(|tab| tab.trim().to_string())
+                    .filter(|tab| !tab.is_empty())
+                    .collect(),
+            );
+        }
+        if let Some(interval) = parse("GOOGLE_SPREADSHEET_BACKUP_INTERVAL")? {
+            self.backup.interval = interval;
+        }
+        if let Some(retention) = parse("GOOGLE_SPREADSHEET_BACKUP_RETENTION")? {
+            self.backup.retention = retention;
+        }
+
+        const TABLE_PREFIX: &str = "GOOGLE_SPREADSHEET_TABLE_";
+        const TABLE_SUFFIX: &str = "_RANGE";
+        for (key, range) in env::vars() {
+            if key.len() > TABLE_PREFIX.len() + TABLE_SUFFIX.len()
+                && key.starts_with(TABLE_PREFIX)
+                && key.ends_with(TABLE_SUFFIX)
+            {
+                let name = key[TABLE_PREFIX.len()..key.len() - TABLE_SUFFIX.len()].to_lowercase();
+                self.tables.entry(name).or_default().range = Some(range);
+            }
+        }
+        Ok(())
+    }
+
+    /// 설정이 올바른지 확인하고, 테이블 설정을 등록된 테이블들에 적용합니다.
+    pub(crate) fn apply(&self, registry: &mut Registry) -> Result<()> {
+        if self.spreadsheet.management_id.is_empty() {
+            bail!("the management spreadsheet id is required (spreadsheet.management_id)");
+        }
+        if self.spreadsheet.backup_id.is_empty() {
+            bail!("the backup spreadsheet id is required (spreadsheet.backup_id)");
+        }
+        if self.interval == 0 {
+            bail!("the interval should be positive");
+        }
+        if self.backup.interval == 0 {
+            bail!("the backup interval should be positive (backup.interval)");
+        }
+        if self.backup.retention == 0 {
+            bail!("the backup retention should be positive (backup.retention)");
+        }
+        validate_range("header_range", &self.header_range)?;
+        validate_range("errors_range", &self.errors_range)?;
+        if let Some(range) = &self.audit.range {
+            validate_range("audit.range", range)?;
+        }
+        if let Some(range) = &self.dry_run.range {
+            validate_range("dry_run.range", range)?;
+        }
+
+        for (name, table) in &self.tables {
+            if let Some(range) = &table.range {
+                validate_range(&format!("tables.{}.range", name), range)?;
+                registry
+                    .set_fields_range(name, range)
+                    .map_err(|e| anyhow!("unknown table in the config (tables.{}): {}", name, e))?;
+            } else if !registry.tables().iter().any(|e| e.name() == name) {
+                bail!("unknown table in the config: tables.{}", name);
+            }
+            if let Some(key) = &table.key {
+                registry.set_key_field(name, key)?;
+            }
+        }
+        for table in registry.tables() {
+            validate_range(
+                &format!("tables.{}.range", table.name()),
+                table.fields_range(),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// 주기를 반환합니다.
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval)
+    }
+
+    /// 백업 정책을 만듭니다.
+    pub(crate) fn backup_policy(&self, registry: &Registry) -> Result<BackupPolicy> {
+        let mut tabs: Vec<String> = match &self.backup.tabs {
+            Some(tabs) => tabs.clone(),
+            None => Some(self.header_range.as_str())
+                .into_iter()
+                .chain(registry.tables().iter().map(|table| table.fields_range()))
+                .map(|range| Ok(range.parse::<MatrixShape>()?.sheet))
+                .collect::<Result<_>>()?,
+        };
+        tabs.sort();
+        tabs.dedup();
+
+        Ok(BackupPolicy {
+            tabs,
+            interval: Duration::from_secs(self.backup.interval),
+            retention: self.backup.retention,
+        })
+    }
+
+    /// 감사 기록을 만듭니다.
+    pub(crate) fn audit_log(&self) -> AuditLog {
+        AuditLog::new(self.audit.range.clone(), self.audit.file.clone())
+    }
+
+    /// 드라이런 중이라면, 의도한 변경 사항을 기록할 곳을 만듭니다.
+    pub(crate) fn dry_run_log(&self) -> Option<AuditLog> {
+        if self.dry_run.enabled {
+            Some(AuditLog::new(
+                self.dry_run.range.clone(),
+                self.dry_run.file.clone(),
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// 처리 상태 저장소를 불러옵니다.
+    pub(crate) fn state_store(&self) -> Result<StateStore> {
+        StateStore::load(self.state.file.clone())
+    }
+
+    /// 주어진 테이블의 controller 설정을 불러옵니다.
+    ///
+    /// 설정이 없다면 빈 테이블로부터 불러옵니다.
+    pub fn options<T>(&self, table: &str) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        let options = self
+            .tables
+            .get(table)
+            .map(|e| e.options.clone())
+            .unwrap_or_default();
+        toml::Value::Table(options)
+            .try_into()
+            .map_err(|e| anyhow!("malformed options (tables.{}.options): {}", table, e))
+    }
+}
+
+fn validate_range(key: &str, range: &str) -> Result<()> {
+    range
+        .parse::<MatrixShape>()
+        .map(|_| ())
+        .map_err(|e| anyhow!("malformed range ({}): {}: {}", key, range, e))
+}
@@ -0,0 +1,237 @@
+use anyhow::Result;
+use serde::{de::DeserializeOwned, Serialize};
+use smartx_whitedog_sheet_client::into_object;
+
+use crate::{
+    context::Context,
+    models::raw::RawField,
+    registry::ControlledTable,
+    state::{ProcessingState, ProcessingStatus},
+    worker::FieldController,
+};
+
+/// 사람이 확인한 작업을 수행한 결과입니다.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Confirmed<F> {
+    /// 수정된 필드 값 (수정하지 않는다면 `None`)
+    pub data: Option<F>,
+    /// 행에 기록할 작업 결과
+    pub outcome: String,
+}
+
+impl<F> ControlledTable<F>
+where
+    F: FieldController + Clone + Serialize + DeserializeOwned,
+{
+    /// 하나의 행을 처리하고, 문서에 반영해야 하는지 여부를 반환합니다.
+    ///
+    /// 1. 수정된 행은 `on_update`로 값을 갱신한 후, `preview`의 결과를 행에 기록하고 확인을 기다립니다.
+    /// 2. 사람이 `confirm`을 체크한 행은 미리보기 이후 수정되지 않은 경우에만 `on_confirm`을 수행하며,
+    ///    수행한 후 `confirm`을 해제하고 결과를 기록합니다.
+    /// 3. 수행하기 전후의 처리 상태를 키 필드의 값으로 저장소에 기록하며, 재시작 전에 완료한 작업은 다시 수행하지 않고
+    ///    중단된 작업은 `reconcile`로 정리합니다.
+    pub(crate) async fn process_row(
+        &self,
+        ctx: &Context<'_>,
+        row: &mut RawField<F>,
+        last: Option<F>,
+    ) -> Result<bool> {
+        let is_changed = row.is_changed()?;
+        let is_confirmed = row.metadata.confirm == Some(true);
+        if !is_changed && !is_confirmed {
+            return Ok(false);
+        }
+
+        if is_changed {
+            // 실패한 행은 해시값을 갱신하지 않으므로, 다음 tick에서 다시 시도합니다.
+            let field = row.data.clone();
+            match field.on_update(ctx, last, Some(row.metadata.clone())).await {
+                Ok(Some(field)) => row.data = field,
+                Ok(None) => {}
+                Err(error) => return Ok(alert_row(row, error)),
+            }
+            row.metadata.hash = Some(row.hash()?);
+
+            // 수정된 행은 새로운 작업으로 취급하되, 중단된 작업은 정리할 때까지 남겨둡니다.
+            // 드라이런에서는 처리 상태를 수정하지 않습니다.
+            let state_key = if ctx.is_dry_run() {
+                None
+            } else {
+                self.state_key(row)?
+            };
+            if let Some((row_key, _)) = state_key {
+                match ctx.state().get(&self.name, &row_key).await {
+                    Some(state) if state.status != ProcessingStatus::InFlight => {
+                        ctx.state().remove(&self.name, &row_key).await?;
+                    }
+                    _ => {}
+                }
+            }
+
+            // 미리보기 이후 수정된 행은 다시 확인을 받습니다.
+            row.metadata.confirm = Some(false);
+            match row.data.preview(ctx, &row.metadata).await {
+                Ok(preview) => {
+                    row.metadata.preview = preview;
+                    row.metadata.alert = None;
+                }
+                Err(error) => {
+                    row.metadata.preview = None;
+                    alert_row(row, error);
+                }
+            }
+            return Ok(true);
+        }
+
+        // 검증에 실패했거나 확인할 작업이 없는 행은 수행하지 않습니다.
+        // 미리보기가 남아있다면 수행에 실패한 작업이므로, 다시 확인받아 수행할 수 있습니다.
+        row.metadata.confirm = Some(false);
+        if row.metadata.preview.is_none() {
+            return Ok(true);
+        }
+
+        // 드라이런에서는 외부에 영향을 주는 작업을 수행하지 않습니다.
+        if ctx.is_dry_run() {
+            row.metadata.preview = Some(format!(
+                "(dry-run) {}",
+                row.metadata.preview.as_deref().unwrap_or_default()
+            ));
+            return Ok(true);
+        }
+
+        // 행이 추가되거나 삭제되어도 다른 행의 작업과 섞이지 않도록, 키 필드의 값으로 행을 구분합니다.
+        let (row_key, idempotency_key) = self
+            .state_key(row)?
+            .ok_or_else(|| anyhow!("a value of the key field is required to confirm the action"))?;
+        let ctx = ctx.with_idempotency_key(&idempotency_key);
+        let state = ProcessingState {
+            table: self.name.clone(),
+            row_key: row_key.clone(),
+            idempotency_key: idempotency_key.clone(),
+            status: ProcessingStatus::InFlight,
+            updated_at: ctx.now(),
+            outcome: None,
+            data: None,
+        };
+
+        let confirmed = match ctx.state().get(&self.name, &row_key).await {
+            // 완료한 작업은 다시 수행하지 않고 기록된 결과를 반영합니다.
+            // 수정된 행의 상태는 지워지므로, 결과로 값이 바뀌어 작업의 키가 달라졌더라도 같은 작업입니다.
+            Some(last) if last.status == ProcessingStatus::Done => {
+                if last.idempotency_key != idempotency_key {
+                    info!(
+                        "refused to repeat the completed action on the row {} of {}",
+                        &row_key, &self.name
+                    );
+                }
+                Confirmed {
+                    data: last.data.map(serde_json::from_value).transpose()?,
+                    outcome: last.outcome.unwrap_or_default(),
+                }
+            }
+            // 중단된 작업은 다시 수행하지 않고 정리합니다.
+            Some(last) if last.status == ProcessingStatus::InFlight => {
+                let reconcile_ctx = ctx.with_idempotency_key(&last.idempotency_key);
+                match row.data.reconcile(&reconcile_ctx, &row.metadata).await {
+                    Ok(Some(confirmed)) => confirmed,
+                    Ok(None) => {
+                        warn!(
+                            "the action on the row {} of {} has been interrupted",
+                            &row_key, &self.name
+                        );
+                        ctx.state()
+                            .set(ProcessingState {
+                                status: ProcessingStatus::Pending,
+                                ..last
+                            })
+                            .await?;
+                        row.metadata.preview = Some(format!(
+                            "(interrupted) {}",
+                            row.metadata.preview.as_deref().unwrap_or_default()
+                        ));
+                        row.metadata.alert = None;
+                        return Ok(true);
+                    }
+                    Err(error) => {
+                        alert_row(row, error);
+                        return Ok(true);
+                    }
+                }
+            }
+            _ => {
+                ctx.state().set(state.clone()).await?;
+                let field = row.data.clone();
+                match field.on_confirm(&ctx, row.metadata.clone()).await {
+                    Ok(confirmed) => confirmed,
+                    Err(error) => {
+                        // 실패한 작업은 다시 확인받은 후 수행할 수 있도록 상태를 지웁니다.
+                        ctx.state().remove(&self.name, &row_key).await?;
+                        alert_row(row, error);
+                        return Ok(true);
+                    }
+                }
+            }
+        };
+
+        // 결과를 문서에 반영하기 전에 완료를 기록하여, 반영에 실패하더라도 다시 수행하지 않습니다.
+        let done = ProcessingState {
+            status: ProcessingStatus::Done,
+            outcome: Some(confirmed.outcome.clone()),
+            data: confirmed
+                .data
+                .as_ref()
+                .map(serde_json::to_value)
+                .transpose()?,
+            ..state
+        };
+        ctx.state().set(done.clone()).await?;
+
+        if let Some(field) = confirmed.data {
+            row.data = field;
+            row.metadata.hash = Some(row.hash()?);
+
+            // 결과로 행의 키가 바뀌었다면, 바뀐 키로도 완료를 기록하여 다시 수행하지 않습니다.
+            if let Some((result_key, _)) = self.state_key(row)? {
+                if result_key != row_key {
+                    ctx.state()
+                        .set(ProcessingState {
+                            row_key: result_key,
+                            ..done
+                        })
+                        .await?;
+                }
+            }
+        }
+        info!("confirmed: {}", &confirmed.outcome);
+        row.metadata.preview = Some(confirmed.outcome);
+        row.metadata.alert = None;
+        Ok(true)
+    }
+
+    /// 처리 상태 저장소에서 행을 구분하는 키와, 현재 값에 대한 작업의 키를 만듭니다.
+    ///
+    /// 행 번호는 행이 추가되거나 삭제되면 바뀌므로, 키 필드의 값이 없는 행은 `None`을 반환합니다.
+    pub(crate) fn state_key(&self, row: &RawField<F>) -> Result<Option<(String, String)>> {
+        let row_key = match self.key(&into_object(serde_json::to_value(row)?)) {
+            Some(row_key) => row_key,
+            None => return Ok(None),
+        };
+        let idempotency_key = ProcessingState::idempotency_key(
+            &self.name,
+            &row_key,
+            row.metadata.hash.as_deref().unwrap_or_default(),
+        );
+        Ok(Some((row_key, idempotency_key)))
+    }
+}
+
+/// 행에 오류를 기록하고, 기록된 오류가 바뀌었는지 여부를 반환합니다.
+pub(crate) fn alert_row<F>(row: &mut RawField<F>, error: anyhow::Error) -> bool {
+    let alert = format!("{:#}", error);
+    if row.metadata.alert.as_ref() == Some(&alert) {
+        false
+    } else {
+        row.metadata.alert = Some(alert);
+        true
+    }
+}
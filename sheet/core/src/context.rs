@@ -0,0 +1,189 @@
+use std::{
+    any::{type_name, Any, TypeId},
+    collections::HashMap,
+    sync::Arc,
+};
+
+use anyhow::Result;
+use smartx_whitedog_common::models::chrono::DateTime;
+use smartx_whitedog_sheet_client::Spreadsheet;
+
+use crate::{audit::AuditLog, config::SheetConfig, models::header::Header, state::StateStore};
+
+/// 현재 시각을 제공합니다.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime;
+}
+
+/// 시스템 시각을 그대로 제공합니다.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime {
+        DateTime::now()
+    }
+}
+
+/// 타입으로 구분되는 공유 서비스 목록입니다.
+///
+/// 타입마다 하나의 값만 저장하며, 같은 타입의 값을 다시 등록하면 덮어씁니다.
+#[derive(Clone, Default)]
+pub struct Services {
+    services: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+}
+
+impl Services {
+    /// 서비스를 등록합니다.
+    pub fn insert<T>(&mut self, service: T) -> &mut Self
+    where
+        T: Send + Sync + 'static,
+    {
+        self.services.insert(TypeId::of::<T>(), Arc::new(service));
+        self
+    }
+
+    /// 주어진 타입의 서비스가 등록되어 있는지 여부를 반환합니다.
+    pub fn contains<T>(&self) -> bool
+    where
+        T: Send + Sync + 'static,
+    {
+        self.services.contains_key(&TypeId::of::<T>())
+    }
+
+    /// 주어진 타입의 서비스를 반환합니다.
+    pub fn get<T>(&self) -> Option<&T>
+    where
+        T: Send + Sync + 'static,
+    {
+        self.services
+            .get(&TypeId::of::<T>())
+            .and_then(|service| service.downcast_ref())
+    }
+
+    /// 주어진 타입의 서비스를 반환하며, 등록되어 있지 않다면 실패합니다.
+    pub fn require<T>(&self) -> Result<&T>
+    where
+        T: Send + Sync + 'static,
+    {
+        self.get()
+            .ok_or_else(|| anyhow!("no such service: {}", type_name::<T>()))
+    }
+}
+
+/// controller에 전달되는 실행 환경입니다.
+#[derive(Copy, Clone)]
+pub struct Context<'a> {
+    pub(crate) services: &'a Services,
+    pub(crate) header: &'a Header,
+    pub(crate) config: &'a SheetConfig,
+    pub(crate) spreadsheet: &'a Spreadsheet,
+    pub(crate) audit: &'a AuditLog,
+    /// 드라이런에서 의도한 변경 사항을 기록할 곳
+    pub(crate) dry_run: Option<&'a AuditLog>,
+    pub(crate) state: &'a StateStore,
+    pub(crate) table: &'a str,
+    pub(crate) row: u32,
+    pub(crate) now: DateTime,
+    /// 수행 중인 작업을 구분하는 키
+    pub(crate) idempotency_key: Option<&'a str>,
+}
+
+impl<'a> Context<'a> {
+    /// 등록된 공유 서비스 목록을 반환합니다.
+    pub fn services(&self) -> &'a Services {
+        self.services
+    }
+
+    /// 주어진 타입의 서비스를 반환하며, 등록되어 있지 않다면 실패합니다.
+    pub fn service<T>(&self) -> Result<&'a T>
+    where
+        T: Send + Sync + 'static,
+    {
+        self.services.require()
+    }
+
+    /// 관리 문서의 `Header`를 반환합니다.
+    pub fn header(&self) -> &'a Header {
+        self.header
+    }
+
+    /// worker의 설정을 반환합니다.
+    pub fn config(&self) -> &'a SheetConfig {
+        self.config
+    }
+
+    /// 관리 문서를 반환합니다.
+    pub fn spreadsheet(&self) -> &'a Spreadsheet {
+        self.spreadsheet
+    }
+
+    /// 감사 기록을 반환합니다.
+    pub fn audit(&self) -> &'a AuditLog {
+        self.audit
+    }
+
+    /// 드라이런 중인지 여부를 반환합니다.
+    ///
+    /// 드라이런 중에는 문서를 수정하지 않으며, controller도 외부에 영향을 주는 작업을 하지 않아야 합니다.
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run.is_some()
+    }
+
+    pub(crate) fn dry_run(&self) -> Option<&'a AuditLog> {
+        self.dry_run
+    }
+
+    /// 처리 상태 저장소를 반환합니다.
+    pub fn state(&self) -> &'a StateStore {
+        self.state
+    }
+
+    /// 처리 중인 테이블의 이름을 반환합니다.
+    pub fn table(&self) -> &'a str {
+        self.table
+    }
+
+    /// 처리 중인 행 번호를 반환합니다. (테이블 기준 offset)
+    pub fn row(&self) -> u32 {
+        self.row
+    }
+
+    /// tick을 시작한 시각을 반환합니다.
+    ///
+    /// 같은 tick 안에서는 항상 같은 값을 반환합니다.
+    pub fn now(&self) -> DateTime {
+        self.now
+    }
+
+    /// 수행 중인 작업을 구분하는 키를 반환합니다.
+    ///
+    /// `on_confirm`과 `reconcile`에서만 주어지며, 같은 행의 같은 값에 대한 작업은 항상 같은 키를 가집니다.
+    /// 외부 서비스에 요청할 때 함께 전달하면, 중복된 요청을 구분할 수 있습니다.
+    pub fn idempotency_key(&self) -> Option<&'a str> {
+        self.idempotency_key
+    }
+
+    /// 주어진 테이블의 controller 설정을 불러옵니다.
+    pub fn options<T>(&self) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        self.config.options(self.table)
+    }
+
+    pub(crate) fn with_table(self, table: &'a str) -> Self {
+        Self { table, ..self }
+    }
+
+    pub(crate) fn with_row(self, row: u32) -> Self {
+        Self { row, ..self }
+    }
+
+    pub(crate) fn with_idempotency_key(self, idempotency_key: &'a str) -> Self {
+        Self {
+            idempotency_key: Some(idempotency_key),
+            ..self
+        }
+    }
+}
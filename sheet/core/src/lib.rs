@@ -1,4 +1,6 @@
 #[macro_use]
+extern crate anyhow;
+#[macro_use]
 extern crate async_trait;
 #[macro_use]
 extern crate log;
@@ -7,7 +9,34 @@ extern crate schemars;
 #[macro_use]
 extern crate serde;
 
+mod audit;
+mod backup;
+mod config;
+mod confirm;
+mod context;
+mod migration;
 pub mod models;
+mod registry;
+mod state;
+#[cfg(feature = "testing")]
+mod testing;
 pub mod worker;
 
 pub use smartx_whitedog_common::init;
+
+pub use self::{
+    audit::{AuditEntry, AuditLog},
+    backup::BackupPolicy,
+    config::{
+        AuditConfig, BackupConfig, DryRunConfig, SheetConfig, SpreadsheetConfig, StateConfig,
+        TableConfig,
+    },
+    confirm::Confirmed,
+    context::{Clock, Context, Services, SystemClock},
+    migration::Migration,
+    registry::{Registry, TableRegistry},
+    state::{ProcessingState, ProcessingStatus, StateStore},
+};
+
+#[cfg(feature = "testing")]
+pub use self::testing::{ManualClock, TestHarness};
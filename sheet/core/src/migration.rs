@@ -0,0 +1,131 @@
+use std::collections::BTreeSet;
+
+use anyhow::Result;
+use smartx_whitedog_common::models::chrono::DateTimeFormat;
+use smartx_whitedog_sheet_client::{Spreadsheet, Table};
+
+use crate::{models::header::Header, registry::TableRegistry, worker::SheetWorker};
+
+/// 이전 버전의 문서 구조를 다음 버전으로 변환하는 단계입니다.
+#[async_trait]
+pub trait Migration: Send + Sync {
+    /// 변환 전 버전
+    fn source_version(&self) -> &str;
+
+    /// 변환 후 버전
+    fn target_version(&self) -> &str;
+
+    /// 관리 문서를 변환합니다.
+    async fn migrate(&self, spreadsheet: &Spreadsheet) -> Result<()>;
+}
+
+impl<R> SheetWorker<R>
+where
+    R: TableRegistry,
+{
+    /// 문서의 `Header`가 registry와 호환되는지 확인하고, 필요하다면 문서를 최신 버전으로 변환합니다.
+    ///
+    /// 호환되지 않는다면 `Header`에 알림을 남기고 `false`를 반환합니다.
+    pub(crate) async fn check_header(
+        &self,
+        table: &Table<'_, Header>,
+        header: &mut Header,
+    ) -> Result<bool> {
+        if header.application.as_deref() != Some(R::APPLICATION)
+            || header.format.as_deref() != Some(R::FORMAT)
+        {
+            let alert = format!(
+                "unsupported application/format: {}/{} (expected: {}/{})",
+                header.application.as_deref().unwrap_or_default(),
+                header.format.as_deref().unwrap_or_default(),
+                R::APPLICATION,
+                R::FORMAT,
+            );
+            return self.alert_header(table, header, alert).await;
+        }
+
+        // 변환하기 전에 최신 버전까지의 단계들을 모두 찾으며, 단계가 순환한다면 아무것도 변환하지 않습니다.
+        let migrations = R::migrations();
+        let mut steps = vec![];
+        let mut visited = BTreeSet::new();
+        let mut version = header.version.clone().unwrap_or_default();
+        while version != R::VERSION {
+            if !visited.insert(version.clone()) {
+                let alert = format!(
+                    "the migrations loop at version {} (expected: {})",
+                    &version,
+                    R::VERSION
+                );
+                return self.alert_header(table, header, alert).await;
+            }
+            let migration = match migrations.iter().find(|e| e.source_version() == version) {
+                Some(migration) => migration,
+                None => {
+                    let alert = format!(
+                        "unsupported version: {} (expected: {})",
+                        &version,
+                        R::VERSION
+                    );
+                    return self.alert_header(table, header, alert).await;
+                }
+            };
+            version = migration.target_version().to_string();
+            steps.push(migration);
+        }
+
+        if !steps.is_empty() && self.is_dry_run() {
+            warn!(
+                "[dry-run] skipped the migration from {} to {}",
+                header.version.as_deref().unwrap_or_default(),
+                R::VERSION
+            );
+            return Ok(false);
+        }
+
+        // 변환에 실패하더라도 되돌릴 수 있도록, 변환 전에 백업합니다.
+        if !steps.is_empty() {
+            self.backup().await?;
+        }
+        for migration in steps {
+            if let Err(error) = migration.migrate(&self.spreadsheet_management).await {
+                let alert = format!(
+                    "failed to migrate from {} to {}: {}",
+                    migration.source_version(),
+                    migration.target_version(),
+                    error,
+                );
+                return self.alert_header(table, header, alert).await;
+            }
+
+            info!(
+                "migrated from {} to {}",
+                migration.source_version(),
+                migration.target_version()
+            );
+            header.version = Some(migration.target_version().to_string());
+            header.updated_date = Some(self.now()?.format(DateTimeFormat::YYYYMMDD));
+            header.alert = None;
+            table.set_rows(std::slice::from_ref(header), 0).await?;
+        }
+
+        if header.alert.is_some() && !self.is_dry_run() {
+            header.alert = None;
+            table.set_rows(std::slice::from_ref(header), 0).await?;
+        }
+        Ok(true)
+    }
+
+    async fn alert_header(
+        &self,
+        table: &Table<'_, Header>,
+        header: &mut Header,
+        alert: String,
+    ) -> Result<bool> {
+        warn!("{}", &alert);
+        if header.alert.as_ref() != Some(&alert) && !self.is_dry_run() {
+            header.alert = Some(alert);
+            table.set_rows(std::slice::from_ref(header), 0).await?;
+        }
+        Ok(false)
+    }
+}
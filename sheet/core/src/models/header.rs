@@ -6,4 +6,8 @@ pub struct Header {
     pub updated_date: Option<String>,
     pub version: Option<String>,
     pub activated: Option<bool>,
+    /// 문서가 registry와 호환되지 않는 이유
+    ///
+    /// 이전 버전의 문서와 같이 열이 없다면 알림은 로그에만 남깁니다.
+    pub alert: Option<String>,
 }
@@ -1,6 +1,7 @@
 #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct Metadata {
     pub confirm: Option<bool>,
+    pub preview: Option<String>,
     pub alert: Option<String>,
     pub hash: Option<String>,
 }
@@ -1,3 +1,7 @@
+use anyhow::Result;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
 use super::metadata::Metadata;
 
 #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
@@ -7,3 +11,21 @@ pub struct RawField<F> {
     #[serde(flatten)]
     pub data: F,
 }
+
+impl<F> RawField<F>
+where
+    F: Serialize,
+{
+    /// 메타데이터를 제외한 필드 값의 해시값을 계산합니다.
+    ///
+    /// 구조체에 선언된 순서로 직렬화하므로, 같은 값에 대해 항상 같은 해시값을 반환합니다.
+    pub fn hash(&self) -> Result<String> {
+        let data = serde_json::to_vec(&self.data)?;
+        Ok(format!("{:x}", Sha256::digest(&data)))
+    }
+
+    /// 마지막으로 저장된 해시값과 비교하여, 새로 추가되었거나 수정된 행인지 여부를 반환합니다.
+    pub fn is_changed(&self) -> Result<bool> {
+        Ok(self.metadata.hash.as_deref() != Some(self.hash()?.as_str()))
+    }
+}
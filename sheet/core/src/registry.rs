@@ -0,0 +1,435 @@
+use std::{
+    any::type_name,
+    collections::{BTreeMap, BTreeSet},
+};
+
+use anyhow::Result;
+use schemars::JsonSchema;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+use smartx_whitedog_common::models::chrono::DateTime;
+use smartx_whitedog_sheet_client::{
+    into_object, Column, Conflict, ConflictPolicy, FieldName, Row, SheetTable, Table, WriteStatus,
+};
+use tokio::sync::Mutex;
+
+use crate::{
+    audit::AuditEntry,
+    config::SheetConfig,
+    confirm::alert_row,
+    context::{Context, Services},
+    migration::Migration,
+    models::raw::RawField,
+    worker::FieldController,
+};
+
+/// 관리 문서와, 문서 내에서 관리할 테이블 목록을 정의합니다.
+#[async_trait]
+pub trait TableRegistry: Send + Sync + 'static {
+    /// 관리 문서의 `Header`에 기록된 애플리케이션 이름
+    const APPLICATION: &'static str;
+    /// 관리 문서의 `Header`에 기록된 문서 형식
+    const FORMAT: &'static str;
+    /// 현재 문서 구조의 버전
+    const VERSION: &'static str;
+
+    /// 이전 버전의 문서 구조를 현재 버전으로 변환하는 단계들입니다.
+    fn migrations() -> Vec<Box<dyn Migration>> {
+        vec![]
+    }
+
+    /// 관리할 테이블들을 등록합니다.
+    fn register(registry: &mut Registry);
+
+    /// controller들이 공유할 서비스들을 등록합니다.
+    ///
+    /// `SheetClient`는 항상 등록되며, `Arc<dyn Clock>`을 등록하지 않았다면 시스템 시각을 사용합니다.
+    async fn services(config: &SheetConfig, services: &mut Services) -> Result<()> {
+        let _ = (config, services);
+        Ok(())
+    }
+}
+
+/// 관리할 테이블 목록입니다.
+#[derive(Default)]
+pub struct Registry {
+    tables: Vec<Box<dyn ManagedTable>>,
+}
+
+impl Registry {
+    /// 주어진 필드 행 범위의 테이블을 `F`로 관리하도록 등록합니다.
+    ///
+    /// 테이블 이름은 로그와 오류 기록에서 테이블을 구분하는 데 사용합니다.
+    pub fn table<F>(&mut self, name: impl ToString, fields_range: impl ToString) -> &mut Self
+    where
+        F: FieldController + Clone + Serialize + DeserializeOwned + JsonSchema + 'static,
+    {
+        self.tables.push(Box::new(ControlledTable::<F> {
+            name: name.to_string(),
+            fields_range: fields_range.to_string(),
+            key_field: None,
+            columns: vec![],
+            layout: Default::default(),
+            last: Default::default(),
+            previewed: Default::default(),
+        }));
+        self
+    }
+
+    /// 구조체로 정의된 테이블을 `F`로 관리하도록 등록합니다.
+    ///
+    /// 테이블 이름, 필드 행 범위, 키 필드와 열 설정은 `SheetTable`의 정의를 따릅니다.
+    pub fn sheet_table<F>(&mut self) -> &mut Self
+    where
+        F: FieldController
+            + SheetTable
+            + Clone
+            + Serialize
+            + DeserializeOwned
+            + JsonSchema
+            + 'static,
+    {
+        self.tables.push(Box::new(ControlledTable::<F> {
+            name: F::NAME.to_string(),
+            fields_range: F::RANGE.to_string(),
+            key_field: F::KEY.map(ToString::to_string),
+            columns: F::columns(),
+            layout: Default::default(),
+            last: Default::default(),
+            previewed: Default::default(),
+        }));
+        self
+    }
+
+    /// 등록된 테이블의 필드 행 범위를 바꿉니다.
+    pub fn set_fields_range(&mut self, name: &str, fields_range: impl ToString) -> Result<()> {
+        let table = self
+            .tables
+            .iter_mut()
+            .find(|table| table.name() == name)
+            .ok_or_else(|| anyhow!("no such table: {}", name))?;
+        table.set_fields_range(fields_range.to_string());
+        Ok(())
+    }
+
+    /// 감사 기록에서 주어진 테이블의 행을 구분할 필드를 설정합니다.
+    pub fn set_key_field(&mut self, name: &str, field: impl ToString) -> Result<()> {
+        let table = self
+            .tables
+            .iter_mut()
+            .find(|table| table.name() == name)
+            .ok_or_else(|| anyhow!("no such table: {}", name))?;
+        table.set_key_field(field.to_string());
+        Ok(())
+    }
+
+    pub(crate) fn tables(&self) -> &[Box<dyn ManagedTable>] {
+        &self.tables
+    }
+}
+
+/// 행 단위로 발생한 오류입니다.
+pub(crate) struct RowError {
+    /// 행 번호 (테이블 기준 offset)
+    pub row: u32,
+    pub error: String,
+}
+
+/// 행의 타입과 controller를 감춘 테이블입니다.
+#[async_trait]
+pub(crate) trait ManagedTable: Send + Sync {
+    fn name(&self) -> &str;
+
+    fn fields_range(&self) -> &str;
+
+    fn set_fields_range(&mut self, fields_range: String);
+
+    fn set_key_field(&mut self, field: String);
+
+    fn controller(&self) -> &'static str;
+
+    /// 열 설정을 반환합니다.
+    #[cfg(feature = "testing")]
+    fn columns(&self) -> &[Column];
+
+    /// 필드 행에 표시되는 이름들을 순서대로 반환합니다.
+    #[cfg(feature = "testing")]
+    fn field_labels(&self) -> Vec<String>;
+
+    /// 테이블의 모든 행을 한 번 처리하고, 새로 발생한 행 단위의 오류들을 `errors`에 추가합니다.
+    ///
+    /// 수정한 셀들은 감사 기록에 추가합니다.
+    /// 테이블을 처리하는 도중 실패하더라도, 그 전까지 발생한 행 단위의 오류들은 `errors`에 남습니다.
+    async fn tick(&self, ctx: Context<'_>, errors: &mut Vec<RowError>) -> Result<()>;
+}
+
+pub(crate) struct ControlledTable<F> {
+    pub(crate) name: String,
+    fields_range: String,
+    /// 감사 기록과 처리 상태에서 행을 구분하는 필드
+    key_field: Option<String>,
+    /// 열 설정
+    columns: Vec<Column>,
+    /// 열 설정을 마지막으로 반영한 필드 구조
+    layout: Mutex<Vec<FieldName>>,
+    /// 마지막 tick에서 반영한 행들의 값 (행의 키 -> 값)
+    last: Mutex<BTreeMap<String, F>>,
+    /// 드라이런에서 이미 출력한 변경 사항 (행의 키, 필드) -> 값
+    previewed: Mutex<BTreeMap<(String, String), String>>,
+}
+
+#[async_trait]
+impl<F> ManagedTable for ControlledTable<F>
+where
+    F: FieldController + Clone + Serialize + DeserializeOwned + JsonSchema,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn fields_range(&self) -> &str {
+        &self.fields_range
+    }
+
+    fn set_fields_range(&mut self, fields_range: String) {
+        self.fields_range = fields_range;
+    }
+
+    fn set_key_field(&mut self, field: String) {
+        self.key_field = Some(field);
+    }
+
+    fn controller(&self) -> &'static str {
+        type_name::<F>()
+    }
+
+    #[cfg(feature = "testing")]
+    fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    #[cfg(feature = "testing")]
+    fn field_labels(&self) -> Vec<String> {
+        crate::testing::field_labels::<RawField<F>>(&self.columns)
+    }
+
+    async fn tick(&self, ctx: Context<'_>, errors: &mut Vec<RowError>) -> Result<()> {
+        let spreadsheet = ctx.spreadsheet();
+        let table: Table<RawField<F>> = spreadsheet
+            .get_table_with(&self.fields_range, &self.columns)
+            .await?;
+
+        // 필드 구조가 바뀐 경우에만 보호 범위와 선택할 수 있는 값 목록을 다시 반영합니다.
+        if !self.columns.is_empty() && !ctx.is_dry_run() {
+            let mut layout = self.layout.lock().await;
+            if layout.as_slice() != table.fields() {
+                table.sync_protections().await?;
+                table.sync_choices().await?;
+                *layout = table.fields().to_vec();
+            }
+        }
+
+        // 시트의 남은 빈 행들은 처리하지 않습니다.
+        let base = table.get_filled_rows(None).await?;
+
+        let mut last = self.last.lock().await;
+        let mut rows = base.clone();
+        let mut updated = vec![];
+        for (at, row) in rows.iter_mut().enumerate() {
+            // 오류가 발생한 행은 기록만 하고, 나머지 행들을 계속 처리합니다.
+            let index = row.index;
+            let alert = row.metadata.alert.clone();
+            let ctx = ctx.with_row(index);
+            // 행이 추가되거나 삭제되어 번호가 바뀌어도, 같은 키의 이전 값과 비교합니다.
+            let row_key = self.key(&into_object(serde_json::to_value(&row.data)?));
+            let previous = row_key.and_then(|row_key| last.get(&row_key).cloned());
+            let is_updated = match self.process_row(&ctx, &mut row.data, previous).await {
+                Ok(is_updated) => is_updated,
+                Err(error) => alert_row(&mut row.data, error),
+            };
+            if is_updated {
+                updated.push(at);
+            }
+            if let Some(error) = &row.metadata.alert {
+                if alert.as_ref() != Some(error) {
+                    warn!(
+                        "failed to process the row {} of {}: {}",
+                        index, &self.name, error
+                    );
+                    errors.push(RowError {
+                        row: index,
+                        error: error.clone(),
+                    });
+                }
+            }
+        }
+
+        // 드라이런에서는 반영하는 대신 의도한 변경 사항만 기록합니다.
+        if let Some(dry_run) = ctx.dry_run() {
+            let mut previewed = self.previewed.lock().await;
+            let mut differs = BTreeSet::new();
+            let mut entries = vec![];
+            for at in updated {
+                for entry in self.diff_row(ctx.now(), &base[at], &rows[at], &[])? {
+                    let key = (entry.row_key.clone(), entry.field.clone());
+                    differs.insert(key.clone());
+                    if previewed.get(&key) != Some(&entry.new_value) {
+                        info!(
+                            "[dry-run] {} [{}].{}: {} -> {}",
+                            &entry.table,
+                            &entry.row_key,
+                            &entry.field,
+                            &entry.old_value,
+                            &entry.new_value,
+                        );
+                        previewed.insert(key, entry.new_value.clone());
+                        entries.push(entry);
+                    }
+                }
+            }
+            // 더 이상 달라지지 않는 셀은 다시 바뀌었을 때 출력할 수 있도록 지웁니다.
+            previewed.retain(|key, _| differs.contains(key));
+            if let Err(error) = dry_run.record(spreadsheet, entries).await {
+                warn!(
+                    "failed to write the dry-run diff of {}: {}",
+                    &self.name, error
+                );
+            }
+
+            // 문서에 반영하지 않았으므로, 이전 tick의 값을 유지합니다.
+            return Ok(());
+        }
+
+        // 연속된 행 단위로 나누어, 수정된 행만 반영합니다.
+        let mut is_offline = false;
+        let mut begin = 0;
+        while begin < updated.len() {
+            let mut end = begin + 1;
+            while end < updated.len()
+                && rows[updated[end]].index == rows[updated[end - 1]].index + 1
+            {
+                end += 1;
+            }
+
+            let (start, stop) = (updated[begin], updated[end - 1] + 1);
+            let data = |rows: &[Row<RawField<F>>]| -> Vec<_> {
+                rows.iter().map(|row| row.data.clone()).collect()
+            };
+            let written = table
+                .set_rows_checked(
+                    &data(&base[start..stop]),
+                    &data(&rows[start..stop]),
+                    rows[start].index,
+                    ConflictPolicy::Merge,
+                )
+                .await?;
+            // 연결 문제로 반영하지 못한 행들은 다음 tick에서 현재 문서와 다시 비교하여 반영합니다.
+            if written.status == Some(WriteStatus::Offline) {
+                is_offline = true;
+                begin = end;
+                continue;
+            }
+            let conflicts = written.conflicts;
+            for conflict in &conflicts {
+                warn!(
+                    "conflict on the row {} of {} ({}): kept {} instead of {}",
+                    conflict.row, &self.name, &conflict.field, &conflict.theirs, &conflict.ours,
+                );
+            }
+
+            let mut entries = vec![];
+            for at in start..stop {
+                entries.extend(self.diff_row(ctx.now(), &base[at], &rows[at], &conflicts)?);
+            }
+            if let Err(error) = ctx.audit().record(spreadsheet, entries).await {
+                warn!("failed to write the audit log of {}: {}", &self.name, error);
+            }
+            begin = end;
+        }
+        if !updated.is_empty() {
+            info!("updated {} row(s) of {}", updated.len(), &self.name);
+        }
+
+        // 삭제된 행의 처리 상태는 지워, 저장소가 계속 커지지 않도록 합니다.
+        let mut row_keys = BTreeSet::new();
+        for row in &rows {
+            if let Some((row_key, _)) = self.state_key(&row.data)? {
+                row_keys.insert(row_key);
+            }
+        }
+        ctx.state().retain(&self.name, &row_keys).await?;
+
+        // 연결 문제로 반영하지 못한 수정 사항이 있다면, 이전 tick의 값을 유지합니다.
+        if is_offline {
+            warn!(
+                "the changes on {} will be retried on the next tick",
+                &self.name
+            );
+        } else {
+            last.clear();
+            for row in rows {
+                if let Some(row_key) = self.key(&into_object(serde_json::to_value(&row.data)?)) {
+                    last.insert(row_key, row.data.data);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<F> ControlledTable<F>
+where
+    F: FieldController + Serialize,
+{
+    /// 행의 수정된 셀들을 비교합니다.
+    ///
+    /// 충돌한 셀은 수정하지 않았으므로 포함하지 않습니다.
+    fn diff_row(
+        &self,
+        now: DateTime,
+        base: &Row<RawField<F>>,
+        ours: &Row<RawField<F>>,
+        conflicts: &[Conflict],
+    ) -> Result<Vec<AuditEntry>> {
+        let index = ours.index;
+        let base = into_object(serde_json::to_value(&base.data)?);
+        let ours = into_object(serde_json::to_value(&ours.data)?);
+        let row_key = self.row_key(index as usize, &ours);
+
+        Ok(ours
+            .iter()
+            .filter(|(field, new_value)| base.get(*field).unwrap_or(&Value::Null) != *new_value)
+            .filter(|(field, _)| {
+                !conflicts
+                    .iter()
+                    .any(|e| e.row == index && &e.field == *field)
+            })
+            .map(|(field, new_value)| {
+                AuditEntry::new(
+                    now,
+                    &self.name,
+                    row_key.clone(),
+                    field,
+                    base.get(field).unwrap_or(&Value::Null),
+                    new_value,
+                    type_name::<F>(),
+                )
+            })
+            .collect())
+    }
+
+    /// 감사 기록에서 행을 구분하는 키를 만듭니다.
+    pub(crate) fn row_key(&self, index: usize, row: &Map<String, Value>) -> String {
+        self.key(row).unwrap_or_else(|| index.to_string())
+    }
+
+    /// 키 필드의 값을 반환합니다. (키 필드가 없거나 값이 비어있다면 `None`)
+    pub(crate) fn key(&self, row: &Map<String, Value>) -> Option<String> {
+        match self.key_field.as_ref().and_then(|field| row.get(field))? {
+            Value::Null => None,
+            Value::String(key) if key.trim().is_empty() => None,
+            Value::String(key) => Some(key.clone()),
+            key => Some(key.to_string()),
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::PathBuf,
+};
+
+use anyhow::Result;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use smartx_whitedog_common::models::chrono::DateTime;
+use tokio::sync::Mutex;
+
+/// 사람이 확인한 작업의 처리 상태입니다.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProcessingStatus {
+    /// 수행을 기다리는 중 (중단된 작업을 다시 확인받는 경우 포함)
+    Pending,
+    /// 수행하는 중
+    InFlight,
+    /// 수행을 완료했으나, 아직 결과를 문서에 반영하지 못했을 수 있음
+    Done,
+}
+
+/// 행 하나의 처리 상태입니다.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProcessingState {
+    /// 테이블 이름
+    pub table: String,
+    /// 행의 키 필드 값
+    pub row_key: String,
+    /// 작업을 구분하는 키
+    ///
+    /// 같은 행의 같은 값에 대한 작업은 항상 같은 키를 가집니다.
+    pub idempotency_key: String,
+    pub status: ProcessingStatus,
+    /// 상태가 바뀐 시각
+    pub updated_at: DateTime,
+    /// 수행한 작업의 결과
+    pub outcome: Option<String>,
+    /// 수행한 작업이 수정한 필드 값 (JSON)
+    pub data: Option<Value>,
+}
+
+impl ProcessingState {
+    /// 주어진 행의 값에 대한 작업의 키를 만듭니다.
+    pub fn idempotency_key(table: &str, row_key: &str, hash: &str) -> String {
+        let mut hasher = Sha256::new();
+        for part in &[table, row_key, hash] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        format!("{:x}", hasher.finalize())
+    }
+}
+
+/// 행 단위의 처리 상태를 보관하는 로컬 저장소입니다.
+///
+/// 상태가 바뀔 때마다 파일에 기록하므로, worker가 재시작하더라도 수행하던 작업을 다시 수행하지 않고 정리할 수 있습니다.
+/// 파일이 주어지지 않았다면 메모리에만 보관합니다.
+pub struct StateStore {
+    file: Option<PathBuf>,
+    states: Mutex<BTreeMap<(String, String), ProcessingState>>,
+}
+
+impl StateStore {
+    /// 주어진 파일에 기록된 상태들을 불러옵니다.
+    pub fn load(file: Option<PathBuf>) -> Result<Self> {
+        let states: Vec<ProcessingState> = match &file {
+            Some(path) if path.exists() => serde_json::from_reader(fs::File::open(path)?)
+                .map_err(|e| anyhow!("malformed state file {}: {}", path.display(), e))?,
+            _ => vec![],
+        };
+
+        let in_flight = states
+            .iter()
+            .filter(|state| state.status == ProcessingStatus::InFlight)
+            .count();
+        if in_flight > 0 {
+            warn!("found {} interrupted action(s) to reconcile", in_flight);
+        }
+
+        Ok(Self {
+            file,
+            states: Mutex::new(
+                states
+                    .into_iter()
+                    .map(|state| ((state.table.clone(), state.row_key.clone()), state))
+                    .collect(),
+            ),
+        })
+    }
+
+    /// 주어진 행의 상태를 반환합니다.
+    pub async fn get(&self, table: &str, row_key: &str) -> Option<ProcessingState> {
+        self.states
+            .lock()
+            .await
+            .get(&(table.to_string(), row_key.to_string()))
+            .cloned()
+    }
+
+    /// 아직 정리하지 않은, 수행하던 도중 중단된 작업들을 반환합니다.
+    pub async fn in_flight(&self) -> Vec<ProcessingState> {
+        self.states
+            .lock()
+            .await
+            .values()
+            .filter(|state| state.status == ProcessingStatus::InFlight)
+            .cloned()
+            .collect()
+    }
+
+    /// 행의 상태를 기록합니다.
+    pub(crate) async fn set(&self, state: ProcessingState) -> Result<()> {
+        let mut states = self.states.lock().await;
+        states.insert((state.table.clone(), state.row_key.clone()), state);
+        self.save(&states)
+    }
+
+    /// 행의 상태를 삭제합니다.
+    pub(crate) async fn remove(&self, table: &str, row_key: &str) -> Result<()> {
+        let mut states = self.states.lock().await;
+        if states
+            .remove(&(table.to_string(), row_key.to_string()))
+            .is_some()
+        {
+            self.save(&states)?;
+        }
+        Ok(())
+    }
+
+    /// 주어진 테이블에서 키가 남아있지 않은 행들의 상태를 삭제합니다.
+    ///
+    /// 수행하던 도중 중단된 작업은 정리할 때까지 남겨둡니다.
+    pub(crate) async fn retain(&self, table: &str, row_keys: &BTreeSet<String>) -> Result<()> {
+        let mut states = self.states.lock().await;
+        let len = states.len();
+        states.retain(|(state_table, row_key), state| {
+            state_table != table
+                || state.status == ProcessingStatus::InFlight
+                || row_keys.contains(row_key)
+        });
+        if states.len() != len {
+            self.save(&states)?;
+        }
+        Ok(())
+    }
+
+    fn save(&self, states: &BTreeMap<(String, String), ProcessingState>) -> Result<()> {
+        let path = match &self.file {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+
+        // 쓰는 도중에 프로그램이 종료되더라도 기존 파일이 손상되지 않도록 합니다.
+        let path_tmp = path.with_extension("tmp");
+        let file = fs::File::create(&path_tmp)?;
+        serde_json::to_writer(&file, &states.values().collect::<Vec<_>>())?;
+        file.sync_all()?;
+        fs::rename(path_tmp, path)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,560 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::Result;
+use schemars::JsonSchema;
+use serde::{de::DeserializeOwned, Serialize};
+use smartx_whitedog_common::models::chrono::DateTime;
+use smartx_whitedog_sheet_client::{
+    Column, MatrixShape, MemorySheets, Row, SheetClient, Spreadsheet, Table,
+};
+use tokio::time::Instant;
+
+use crate::{
+    audit::AuditEntry,
+    config::{SheetConfig, SpreadsheetConfig},
+    context::Clock,
+    models::{header::Header, metadata::Metadata, raw::RawField},
+    registry::{ManagedTable, TableRegistry},
+    worker::{AsyncWorker, SheetWorker},
+};
+
+/// 테스트에서 직접 조작하는 시각입니다.
+#[derive(Debug)]
+pub struct ManualClock {
+    now: Mutex<DateTime>,
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new(DateTime::now())
+    }
+}
+
+impl ManualClock {
+    pub fn new(now: DateTime) -> Self {
+        Self {
+            now: Mutex::new(now),
+        }
+    }
+
+    /// 시각을 바꿉니다.
+    pub fn set(&self, now: DateTime) {
+        *self.now.lock().unwrap() = now;
+    }
+
+    /// 주어진 시간만큼 시각을 진행합니다.
+    pub fn advance(&self, duration: Duration) {
+        let mut now = self.now.lock().unwrap();
+        now.0 += chrono::Duration::from_std(duration).expect("duration out of range");
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime {
+        *self.now.lock().unwrap()
+    }
+}
+
+/// 메모리에 저장되는 문서에 대해 `SheetWorker`를 실행하는 테스트 도구입니다.
+///
+/// 사람의 수정을 흉내내어 문서를 바꾸고 tick을 실행한 후, 결과 행과 메타데이터, 오류를 확인할 수 있습니다.
+/// 관리 문서의 `Header`와 각 테이블의 필드 행은 등록된 테이블들에 맞게 미리 만들어지며,
+/// 처리 상태와 감사 기록은 로컬 파일에 기록하지 않고, 백업은 수행하지 않습니다.
+/// 보호 범위나 데이터 유효성 검사와 같은 요청은 문서에 반영되지 않으며, `MemorySheets::requests`로 확인할 수 있습니다.
+///
+/// ```ignore
+/// let harness = TestHarness::<MyRegistry>::new().await?;
+/// harness.set_rows("purchase", 0, &[Purchase { .. }]).await?;
+/// harness.tick().await?;
+///
+/// harness.confirm("purchase", 0).await?;
+/// harness.clock().advance(Duration::from_secs(60));
+/// harness.tick().await?;
+///
+/// let rows = harness.rows::<Purchase>("purchase").await?;
+/// assert_eq!(rows[0].metadata.alert, None);
+/// ```
+pub struct TestHarness<R> {
+    sheets: MemorySheets,
+    clock: Arc<ManualClock>,
+    worker: SheetWorker<R>,
+}
+
+impl<R> TestHarness<R>
+where
+    R: TableRegistry,
+{
+    /// 관리 문서의 ID
+    pub const MANAGEMENT_ID: &'static str = "management";
+    /// 백업 문서의 ID
+    pub const BACKUP_ID: &'static str = "backup";
+    /// 테이블이 위치한 시트의 행 수
+    pub const SHEET_ROWS: u32 = 1000;
+
+    /// 기본 설정으로 테스트 도구를 만듭니다.
+    pub async fn new() -> Result<Self> {
+        Self::with_config(Default::default()).await
+    }
+
+    /// 주어진 설정으로 테스트 도구를 만듭니다.
+    ///
+    /// 문서 ID와 로컬 파일 경로는 테스트용 값으로 덮어씁니다.
+    pub async fn with_config(mut config: SheetConfig) -> Result<Self> {
+        config.spreadsheet = SpreadsheetConfig {
+            management_id: Self::MANAGEMENT_ID.to_string(),
+            backup_id: Self::BACKUP_ID.to_string(),
+        };
+        config.audit.file = None;
+        config.dry_run.file = None;
+        config.state.file = None;
+
+        let sheets = MemorySheets::default();
+        let clock = Arc::new(ManualClock::default());
+        let client = SheetClient::memory(sheets.clone());
+        let worker = SheetWorker::<R>::build(config, client, Some(clock.clone())).await?;
+
+        // 메모리에 저장되는 문서는 시트 복사를 지원하지 않으므로, 백업을 건너뜁니다.
+        *worker.last_backup.lock().await = Some(Instant::now());
+
+        let harness = Self {
+            sheets,
+            clock,
+            worker,
+        };
+        harness.init().await?;
+        Ok(harness)
+    }
+
+    /// `Header`와 기록용 시트, 등록된 테이블들의 필드 행을 만듭니다.
+    async fn init(&self) -> Result<()> {
+        let config = self.worker.config();
+        self.write_labels(&config.header_range, field_labels::<Header>(&[]))?;
+        let header: Table<Header> = self.spreadsheet().get_table(&config.header_range).await?;
+        let row = Header {
+            application: Some(R::APPLICATION.to_string()),
+            format: Some(R::FORMAT.to_string()),
+            version: Some(R::VERSION.to_string()),
+            activated: Some(true),
+            ..Default::default()
+        };
+        header.set_rows(&[row], 0).await?;
+
+        let errors = ["timestamp", "table", "row", "controller", "error"];
+        self.write_labels(
+            &config.errors_range,
+            errors.iter().map(ToString::to_string).collect(),
+        )?;
+        for range in config.audit.range.iter().chain(&config.dry_run.range) {
+            self.write_labels(range, field_labels::<AuditEntry>(&[]))?;
+            self.pad_rows(range)?;
+        }
+        for table in self.worker.registry.tables() {
+            self.write_labels(table.fields_range(), table.field_labels())?;
+            self.pad_rows(table.fields_range())?;
+        }
+        Ok(())
+    }
+
+    /// Google Sheets의 새 시트와 같이, 주어진 범위의 시트에 빈 행들이 남아있도록 합니다.
+    fn pad_rows(&self, range: &str) -> Result<()> {
+        let shape: MatrixShape = range.parse()?;
+        self.sheets
+            .set_rows(Self::MANAGEMENT_ID, &shape.sheet, Self::SHEET_ROWS);
+        Ok(())
+    }
+
+    fn write_labels(&self, range: &str, labels: Vec<String>) -> Result<()> {
+        self.sheets.set(Self::MANAGEMENT_ID, range, vec![labels])
+    }
+
+    /// 메모리에 저장되는 문서들을 반환합니다.
+    pub fn sheets(&self) -> &MemorySheets {
+        &self.sheets
+    }
+
+    /// worker가 사용하는 시각을 반환합니다.
+    pub fn clock(&self) -> &ManualClock {
+        &self.clock
+    }
+
+    /// 실행할 worker를 반환합니다.
+    pub fn worker(&self) -> &SheetWorker<R> {
+        &self.worker
+    }
+
+    /// 관리 문서를 반환합니다.
+    pub fn spreadsheet(&self) -> &Spreadsheet {
+        &self.worker.spreadsheet_management
+    }
+
+    /// worker의 tick을 한 번 실행합니다.
+    pub async fn tick(&self) -> Result<()> {
+        self.worker.tick().await
+    }
+
+    /// 사람이 수정한 것처럼, 주어진 행부터 필드 값들을 씁니다.
+    ///
+    /// `F`에 선언된 필드의 열만 쓰므로, 메타데이터는 바뀌지 않습니다.
+    pub async fn set_rows<F>(&self, table: &str, offset: u32, rows: &[F]) -> Result<()>
+    where
+        F: Serialize + JsonSchema,
+    {
+        let table = self.table(table)?;
+        self.spreadsheet()
+            .get_table_with::<F>(table.fields_range(), table.columns())
+            .await?
+            .set_rows(rows, offset)
+            .await?;
+        Ok(())
+    }
+
+    /// 사람이 확인한 것처럼, 주어진 행의 `confirm`을 체크합니다.
+    pub async fn confirm(&self, table: &str, row: u32) -> Result<()> {
+        let table = self.table(table)?;
+        let metadata: Table<Metadata> = self.spreadsheet().get_table(table.fields_range()).await?;
+        let mut target = metadata
+            .get_rows(Some(row + 1))
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow!("no such row on {}: {}", table.name(), row))?;
+        target.confirm = Some(true);
+        metadata.set_rows(&[target], row).await?;
+        Ok(())
+    }
+
+    /// 주어진 테이블의 빈 행을 제외한 모든 행을 메타데이터와 함께 읽습니다.
+    pub async fn rows<F>(&self, table: &str) -> Result<Vec<Row<RawField<F>>>>
+    where
+        F: DeserializeOwned + JsonSchema,
+    {
+        let table = self.table(table)?;
+        self.spreadsheet()
+            .get_table_with::<RawField<F>>(table.fields_range(), table.columns())
+            .await?
+            .get_filled_rows(None)
+            .await
+    }
+
+    /// 오류 기록에 추가된 행들을 반환합니다. (시각, 테이블, 행, controller, 오류)
+    pub fn errors(&self) -> Result<Vec<Vec<String>>> {
+        self.records(&self.worker.config().errors_range)
+    }
+
+    /// 감사 기록에 추가된 행들을 반환합니다. (`AuditEntry`의 필드 순서)
+    pub fn audit(&self) -> Result<Vec<Vec<String>>> {
+        match &self.worker.config().audit.range {
+            Some(range) => self.records(range),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// 주어진 필드 행 아래에 추가된 행들을 읽습니다.
+    fn records(&self, range: &str) -> Result<Vec<Vec<String>>> {
+        let mut shape: MatrixShape = range.parse()?;
+        shape.start.row = shape.end.row.map(|row| row + 1);
+        shape.end.row = None;
+        self.sheets.get(Self::MANAGEMENT_ID, &shape.to_string())
+    }
+
+    fn table(&self, name: &str) -> Result<&dyn ManagedTable> {
+        self.worker
+            .registry
+            .tables()
+            .iter()
+            .find(|table| table.name() == name)
+            .map(|table| &**table)
+            .ok_or_else(|| anyhow!("no such table: {}", name))
+    }
+}
+
+/// 필드 행에 표시되는 이름들을 구조체에 선언된 순서로 반환합니다.
+pub(crate) fn field_labels<T>(columns: &[Column]) -> Vec<String>
+where
+    T: JsonSchema,
+{
+    let schema = schemars::schema_for!(T).schema;
+    schema
+        .object
+        .map(|object| {
+            object
+                .properties
+                .keys()
+                .map(|field| {
+                    columns
+                        .iter()
+                        .find(|column| &column.field == field)
+                        .and_then(|column| column.label.clone())
+                        .unwrap_or_else(|| field.clone())
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::{
+        confirm::Confirmed,
+        context::{Context, Services},
+        registry::Registry,
+        state::ProcessingStatus,
+        worker::FieldController,
+    };
+
+    #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+    struct Order {
+        id: Option<String>,
+        count: Option<i64>,
+        status: Option<String>,
+    }
+
+    impl Order {
+        fn new(id: &str, count: i64) -> Self {
+            Self {
+                id: Some(id.to_string()),
+                count: Some(count),
+                status: None,
+            }
+        }
+    }
+
+    /// 외부 서비스에 요청한 횟수를 세고, 요청의 실패를 흉내냅니다.
+    #[derive(Default)]
+    struct Orders {
+        submitted: AtomicUsize,
+        fail: AtomicBool,
+    }
+
+    #[async_trait]
+    impl FieldController for Order {
+        async fn on_update(
+            self,
+            _ctx: &Context<'_>,
+            _last: Option<Self>,
+            _metadata: Option<Metadata>,
+        ) -> Result<Option<Self>> {
+            if self.count.unwrap_or_default() < 0 {
+                bail!("the count should not be negative");
+            }
+            Ok(None)
+        }
+
+        async fn preview(
+            &self,
+            _ctx: &Context<'_>,
+            _metadata: &Metadata,
+        ) -> Result<Option<String>> {
+            Ok(match (&self.status, self.count) {
+                (None, Some(count)) => Some(format!("order {} item(s)", count)),
+                _ => None,
+            })
+        }
+
+        async fn on_confirm(
+            self,
+            ctx: &Context<'_>,
+            _metadata: Metadata,
+        ) -> Result<Confirmed<Self>> {
+            let orders: &Orders = ctx.service()?;
+            if orders.fail.load(Ordering::SeqCst) {
+                bail!("the order service is unavailable");
+            }
+            orders.submitted.fetch_add(1, Ordering::SeqCst);
+            Ok(Confirmed {
+                data: Some(Self {
+                    status: Some("ordered".to_string()),
+                    ..self
+                }),
+                outcome: "ordered".to_string(),
+            })
+        }
+    }
+
+    struct TestRegistry;
+
+    #[async_trait]
+    impl TableRegistry for TestRegistry {
+        const APPLICATION: &'static str = "test";
+        const FORMAT: &'static str = "orders";
+        const VERSION: &'static str = "1";
+
+        fn register(registry: &mut Registry) {
+            registry.table::<Order>("order", "Order!A1:G1");
+            registry.set_key_field("order", "id").unwrap();
+        }
+
+        async fn services(_config: &SheetConfig, services: &mut Services) -> Result<()> {
+            services.insert(Orders::default());
+            Ok(())
+        }
+    }
+
+    type Harness = TestHarness<TestRegistry>;
+
+    fn orders(harness: &Harness) -> &Orders {
+        harness.worker().services().require().unwrap()
+    }
+
+    async fn order(harness: &Harness) -> Result<RawField<Order>> {
+        let mut rows = harness.rows::<Order>("order").await?;
+        assert_eq!(rows.len(), 1);
+        Ok(rows.remove(0).data)
+    }
+
+    #[tokio::test]
+    async fn test_confirm_order() -> Result<()> {
+        let harness = Harness::new().await?;
+        harness.set_rows("order", 0, &[Order::new("a", 2)]).await?;
+        harness.tick().await?;
+
+        let row = order(&harness).await?;
+        assert_eq!(row.metadata.preview.as_deref(), Some("order 2 item(s)"));
+        assert_eq!(row.metadata.confirm, Some(false));
+        assert_eq!(row.metadata.alert, None);
+
+        // 시트의 남은 빈 행들은 건드리지 않습니다.
+        let blank = harness.sheets().get(Harness::MANAGEMENT_ID, "Order!A3:G")?;
+        assert!(blank.is_empty());
+
+        harness.confirm("order", 0).await?;
+        harness.tick().await?;
+
+        let row = order(&harness).await?;
+        assert_eq!(row.data.status.as_deref(), Some("ordered"));
+        assert_eq!(row.metadata.preview.as_deref(), Some("ordered"));
+        assert_eq!(row.metadata.confirm, Some(false));
+        assert_eq!(orders(&harness).submitted.load(Ordering::SeqCst), 1);
+
+        // 결과로 값이 바뀌었더라도, 완료한 작업은 다시 수행하지 않습니다.
+        harness.confirm("order", 0).await?;
+        harness.tick().await?;
+
+        let row = order(&harness).await?;
+        assert_eq!(row.data.status.as_deref(), Some("ordered"));
+        assert_eq!(orders(&harness).submitted.load(Ordering::SeqCst), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_alert_invalid_order() -> Result<()> {
+        let harness = Harness::new().await?;
+        harness.set_rows("order", 0, &[Order::new("a", -1)]).await?;
+        harness.tick().await?;
+        harness.tick().await?;
+
+        let row = order(&harness).await?;
+        assert_eq!(
+            row.metadata.alert.as_deref(),
+            Some("the count should not be negative"),
+        );
+
+        // 같은 오류는 한 번만 기록하며, 사람이 보는 시트의 행 번호와 worker의 시각을 사용합니다.
+        let errors = harness.errors()?;
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0][0], harness.clock().now().0.to_rfc3339());
+        assert_eq!(errors[0][1], "order");
+        assert_eq!(errors[0][2], "2");
+        assert_eq!(errors[0][4], "the count should not be negative");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_retry_failed_confirm() -> Result<()> {
+        let harness = Harness::new().await?;
+        harness.set_rows("order", 0, &[Order::new("a", 2)]).await?;
+        harness.tick().await?;
+
+        orders(&harness).fail.store(true, Ordering::SeqCst);
+        harness.confirm("order", 0).await?;
+        harness.tick().await?;
+
+        let row = order(&harness).await?;
+        assert_eq!(row.data.status, None);
+        assert_eq!(
+            row.metadata.alert.as_deref(),
+            Some("the order service is unavailable"),
+        );
+
+        // 수행에 실패한 작업은 값을 고치지 않고도 다시 확인받아 수행할 수 있습니다.
+        orders(&harness).fail.store(false, Ordering::SeqCst);
+        harness.confirm("order", 0).await?;
+        harness.tick().await?;
+
+        let row = order(&harness).await?;
+        assert_eq!(row.data.status.as_deref(), Some("ordered"));
+        assert_eq!(row.metadata.alert, None);
+        assert_eq!(orders(&harness).submitted.load(Ordering::SeqCst), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_require_key_to_confirm() -> Result<()> {
+        let harness = Harness::new().await?;
+        let order_without_id = Order {
+            id: None,
+            ..Order::new("a", 2)
+        };
+        harness.set_rows("order", 0, &[order_without_id]).await?;
+        harness.tick().await?;
+        harness.confirm("order", 0).await?;
+        harness.tick().await?;
+
+        let row = order(&harness).await?;
+        assert_eq!(row.data.status, None);
+        assert_eq!(
+            row.metadata.alert.as_deref(),
+            Some("a value of the key field is required to confirm the action"),
+        );
+        assert_eq!(orders(&harness).submitted.load(Ordering::SeqCst), 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_audit_history() -> Result<()> {
+        let mut config = SheetConfig::default();
+        config.audit.range = Some("Audit!A1:G1".to_string());
+        let harness = Harness::with_config(config).await?;
+        harness.set_rows("order", 0, &[Order::new("a", 2)]).await?;
+        harness.tick().await?;
+        harness.confirm("order", 0).await?;
+        harness.tick().await?;
+
+        // 감사 시트의 남은 빈 행들은 기록으로 읽지 않습니다.
+        let history = harness.worker().history("order", "a").await?;
+        let status = history
+            .iter()
+            .find(|entry| entry.field == "status")
+            .expect("the status should be audited");
+        assert_eq!(status.old_value, "null");
+        assert_eq!(status.new_value, "\"ordered\"");
+        assert!(harness.worker().history("order", "b").await?.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_prune_removed_rows() -> Result<()> {
+        let harness = Harness::new().await?;
+        harness.set_rows("order", 0, &[Order::new("a", 2)]).await?;
+        harness.tick().await?;
+        harness.confirm("order", 0).await?;
+        harness.tick().await?;
+
+        let state = harness.worker().state().get("order", "a").await;
+        assert_eq!(
+            state.map(|state| state.status),
+            Some(ProcessingStatus::Done)
+        );
+
+        // 키가 바뀌어 더 이상 존재하지 않는 행의 처리 상태는 지웁니다.
+        harness.set_rows("order", 0, &[Order::new("b", 2)]).await?;
+        harness.tick().await?;
+        assert_eq!(harness.worker().state().get("order", "a").await, None);
+        Ok(())
+    }
+}
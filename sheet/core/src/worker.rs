@@ -1,75 +1,341 @@
-use std::{env, marker::PhantomData, time::Duration};
+use std::{collections::BTreeMap, marker::PhantomData, sync::Arc, time::Duration};
 
 use anyhow::Result;
-use smartx_whitedog_sheet_client::{SheetClient, Spreadsheet};
+use smartx_whitedog_common::models::chrono::DateTime;
+use smartx_whitedog_sheet_client::{MatrixShape, SheetClient, Spreadsheet, Table};
+use tokio::{sync::Mutex, time::Instant};
 
-use crate::models::metadata::Metadata;
+use crate::{
+    audit::{AuditEntry, AuditLog},
+    backup::BackupPolicy,
+    config::SheetConfig,
+    confirm::Confirmed,
+    context::{Clock, Context, Services, SystemClock},
+    models::{header::Header, metadata::Metadata},
+    registry::{ManagedTable, Registry, TableRegistry},
+    state::StateStore,
+};
 
 pub use smartx_whitedog_common::worker::AsyncWorker;
 
 /// 필드 값을 관리합니다.
 #[async_trait]
-pub trait FieldController {
+pub trait FieldController: Send + Sync {
+    /// 새로 추가되었거나 수정된 행을 처리하고, 수정할 값을 반환합니다.
+    ///
+    /// `last`는 이전 tick에서 반영한 같은 위치의 행입니다.
     async fn on_update(
         self,
+        ctx: &Context<'_>,
         last: Option<Self>,
         metadata: Option<Metadata>,
     ) -> Result<Option<Self>>
     where
         Self: Sized;
+
+    /// 사람의 확인이 필요한 작업의 미리보기를 만듭니다.
+    ///
+    /// 확인이 필요한 작업이 없다면 `None`을 반환하며, 검증에 실패했다면 오류를 반환합니다.
+    async fn preview(&self, ctx: &Context<'_>, metadata: &Metadata) -> Result<Option<String>> {
+        let _ = (ctx, metadata);
+        Ok(None)
+    }
+
+    /// 사람이 확인한 작업을 수행합니다.
+    async fn on_confirm(self, ctx: &Context<'_>, metadata: Metadata) -> Result<Confirmed<Self>>
+    where
+        Self: Sized,
+    {
+        let _ = (ctx, metadata);
+        bail!("nothing to confirm")
+    }
+
+    /// `on_confirm`을 수행하던 도중 worker가 종료되어 완료 여부를 알 수 없는 작업을 정리합니다.
+    ///
+    /// 중단된 작업은 다시 수행하는 대신 이 함수로 정리하며, `ctx.idempotency_key()`로 작업을 구분할 수 있습니다.
+    /// 작업이 이미 완료되었다면 그 결과를 반환하며, `None`을 반환하면 사람이 다시 확인한 후에 수행합니다.
+    async fn reconcile(
+        &self,
+        ctx: &Context<'_>,
+        metadata: &Metadata,
+    ) -> Result<Option<Confirmed<Self>>>
+    where
+        Self: Sized,
+    {
+        let _ = (ctx, metadata);
+        Ok(None)
+    }
 }
 
 /// 주어진 명령에 따라 주기적으로 시트를 관리합니다.
+///
+/// 관리할 테이블들은 `TableRegistry`로 등록하며, 각 테이블은 서로 독립적으로 처리됩니다.
 #[derive(Clone)]
-pub struct SheetWorker<F> {
-    client: SheetClient,
-    spreadsheet_management: Spreadsheet,
-    spreadsheet_backup: Spreadsheet,
-    interval: Duration,
-    _field: PhantomData<F>,
+pub struct SheetWorker<R> {
+    pub(crate) spreadsheet_management: Spreadsheet,
+    pub(crate) spreadsheet_backup: Spreadsheet,
+    pub(crate) backup: BackupPolicy,
+    /// 마지막으로 백업한 시각
+    pub(crate) last_backup: Arc<Mutex<Option<Instant>>>,
+    config: Arc<SheetConfig>,
+    audit: Arc<AuditLog>,
+    dry_run: Option<Arc<AuditLog>>,
+    state: Arc<StateStore>,
+    services: Arc<Services>,
+    pub(crate) registry: Arc<Registry>,
+    /// 테이블마다 마지막으로 기록한 테이블 단위의 오류
+    last_errors: Arc<Mutex<BTreeMap<String, String>>>,
+    _registry: PhantomData<R>,
+}
+
+impl<R> SheetWorker<R> {
+    /// 감사 기록을 반환합니다.
+    pub fn audit(&self) -> &AuditLog {
+        &self.audit
+    }
+
+    /// 주어진 테이블의 행의 수정 기록을 오래된 순서로 불러옵니다.
+    pub async fn history(&self, table: &str, row_key: &str) -> Result<Vec<AuditEntry>> {
+        self.audit
+            .history(&self.spreadsheet_management, table, row_key)
+            .await
+    }
+
+    /// 처리 상태 저장소를 반환합니다.
+    pub fn state(&self) -> &StateStore {
+        &self.state
+    }
+
+    /// 드라이런 중인지 여부를 반환합니다.
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run.is_some()
+    }
+
+    /// 등록된 공유 서비스 목록을 반환합니다.
+    pub fn services(&self) -> &Services {
+        &self.services
+    }
+
+    /// 불러온 설정을 반환합니다.
+    pub fn config(&self) -> &SheetConfig {
+        &self.config
+    }
+
+    /// 등록된 `Clock`으로 현재 시각을 반환합니다.
+    pub(crate) fn now(&self) -> Result<DateTime> {
+        Ok(self.services.require::<Arc<dyn Clock>>()?.now())
+    }
+
+    /// 테이블에서 발생한 오류들을 주어진 시각으로 오류 기록에 추가합니다.
+    async fn log_errors(
+        &self,
+        table: &dyn ManagedTable,
+        errors: Vec<(Option<u32>, String)>,
+        now: DateTime,
+    ) {
+        if self.is_dry_run() {
+            return;
+        }
+        // 테이블 기준 행 번호를 사람이 보는 시트의 행 번호로 바꿉니다. (데이터는 필드 행의 다음 행부터 시작)
+        // 테이블 기준 행 번호를 사람이 보는 시트의 행 번호로 바꿉니다.
+        let first_row = table
+            .fields_range()
+            .parse::<MatrixShape>()
+            .ok()
+            .and_then(|shape| shape.end.row)
+            .unwrap_or_default()
+            + 2;
+        let timestamp = now.0.to_rfc3339();
+        let data = errors
+            .into_iter()
+            .map(|(row, error)| {
+                vec![
+                    timestamp.clone(),
+                    table.name().to_string(),
+                    row.map(|row| (first_row + row).to_string())
+                        .unwrap_or_default(),
+                    table.controller().to_string(),
+                    error,
+                ]
+            })
+            .collect();
+        if let Err(error) = self
+            .spreadsheet_management
+            .append(&self.config.errors_range, data)
+            .await
+        {
+            warn!("failed to write the error log: {}", error);
+        }
+    }
+}
+
+impl<R> SheetWorker<R>
+where
+    R: TableRegistry,
+{
+    /// 주어진 설정과 클라이언트로 worker를 초기화합니다.
+    pub async fn with_client(config: SheetConfig, client: SheetClient) -> Result<Self> {
+        Self::build(config, client, None).await
+    }
+
+    /// worker를 초기화합니다.
+    ///
+    /// `clock`이 주어졌다면, `TableRegistry::services`에서 등록한 시각 대신 사용합니다.
+    pub(crate) async fn build(
+        config: SheetConfig,
+        client: SheetClient,
+        clock: Option<Arc<dyn Clock>>,
+    ) -> Result<Self> {
+        let mut registry = Registry::default();
+        R::register(&mut registry);
+        config.apply(&mut registry)?;
+        let backup = config.backup_policy(&registry)?;
+
+        let mut services = Services::default();
+        services.insert(client.clone());
+        R::services(&config, &mut services).await?;
+        if let Some(clock) = clock {
+            services.insert(clock);
+        } else if !services.contains::<Arc<dyn Clock>>() {
+            services.insert::<Arc<dyn Clock>>(Arc::new(SystemClock));
+        }
+        let spreadsheet_management = client
+            .clone()
+            .into_sheet_unchecked(&config.spreadsheet.management_id);
+        let spreadsheet_backup = client
+            .clone()
+            .into_sheet_unchecked(&config.spreadsheet.backup_id);
+
+        Ok(Self {
+            spreadsheet_management,
+            spreadsheet_backup,
+            backup,
+            last_backup: Default::default(),
+            audit: Arc::new(config.audit_log()),
+            dry_run: config.dry_run_log().map(Arc::new),
+            state: Arc::new(config.state_store()?),
+            services: Arc::new(services),
+            config: Arc::new(config),
+            registry: Arc::new(registry),
+            last_errors: Default::default(),
+            _registry: Default::default(),
+        })
+    }
 }
 
 #[async_trait]
-impl<F> AsyncWorker for SheetWorker<F>
+impl<R> AsyncWorker for SheetWorker<R>
 where
-    F: FieldController + Sync,
+    R: TableRegistry,
 {
     const NAMESPACE: &'static str = "whitedog-sheet-system";
 
     /// Worker를 초기화합니다.
     ///
     /// ## Note
-    /// 이에, 프로그램을 수행하기 위해서는 다음의 환경변수가 필요합니다!
+    /// 설정은 `SheetConfig::load`로 불러오며, 다음의 환경변수는 항상 필요합니다!
     /// * GOOGLE_OAUTH2_SERVICE_ACCOUNT: Google Drive에 접근 가능한 Google service account (json 파일 경로)
-    /// * GOOGLE_SPREADSHEET_MANAGEMENT_ID: 관리하고자 하는 문서 ID
-    /// * GOOGLE_SPREADSHEET_BACKUP_ID: 백업을 위한 문서 ID
     async fn try_new() -> Result<Self>
     where
         Self: Sized,
     {
+        let config = SheetConfig::load()?;
         let client = SheetClient::try_default().await?;
-        let spreadsheet_management = env::var("GOOGLE_SPREADSHEET_MANAGEMENT_ID")?;
-        let spreadsheet_backup = env::var("GOOGLE_SPREADSHEET_BACKUP_ID")?;
-        let interval = Duration::from_secs(10);
-
-        let spreadsheet_management = client.clone().into_sheet_unchecked(spreadsheet_management);
-        let spreadsheet_backup = client.clone().into_sheet_unchecked(spreadsheet_backup);
-
-        Ok(Self {
-            client,
-            spreadsheet_management,
-            spreadsheet_backup,
-            interval,
-            _field: Default::default(),
-        })
+        Self::with_client(config, client).await
     }
 
     fn interval(&self) -> Duration {
-        Duration::from_secs(10)
+        self.config.interval()
+    }
+
+    /// 중단된 작업이 남아있다면, 다음 실행에서 정리됨을 알립니다.
+    async fn shutdown(&self) -> Result<()> {
+        let in_flight = self.state.in_flight().await;
+        if !in_flight.is_empty() {
+            warn!(
+                "{} interrupted action(s) will be reconciled on the next start",
+                in_flight.len(),
+            );
+        }
+        Ok(())
     }
 
     async fn tick(&self) -> Result<()> {
-        info!("hello world");
+        // 백업에 실패하더라도 관리는 계속합니다.
+        if self.is_dry_run() {
+            debug!("[dry-run] skipped the backup");
+        } else if let Err(error) = self.backup_if_due().await {
+            warn!("failed to backup: {}", error);
+        }
+
+        // 관리가 활성화된 경우에만 동작합니다.
+        let header_table: Table<Header> = self
+            .spreadsheet_management
+            .get_table(&self.config.header_range)
+            .await?;
+        let mut header = match header_table.get_rows(Some(1)).await?.pop() {
+            Some(header) if header.activated == Some(true) => header,
+            Some(_) => {
+                debug!("skipped: the sheet system is not activated");
+                return Ok(());
+            }
+            None => bail!(
+                "cannot find the header on \"{}\"",
+                &self.config.header_range
+            ),
+        };
+
+        // 문서가 registry와 호환되는 경우에만 동작합니다.
+        if !self.check_header(&header_table, &mut header).await? {
+            return Ok(());
+        }
+
+        let ctx = Context {
+            services: &self.services,
+            header: &header,
+            config: &self.config,
+            spreadsheet: &self.spreadsheet_management,
+            audit: &self.audit,
+            dry_run: self.dry_run.as_deref(),
+            state: &self.state,
+            table: "",
+            row: 0,
+            now: self.now()?,
+            idempotency_key: None,
+        };
+
+        // 테이블마다 독립적으로 처리하며, 한 테이블의 실패가 다른 테이블에 영향을 주지 않습니다.
+        for table in self.registry.tables() {
+            let mut row_errors = vec![];
+            let result = table
+                .tick(ctx.with_table(table.name()), &mut row_errors)
+                .await;
+            let mut errors: Vec<_> = row_errors
+                .into_iter()
+                .map(|error| (Some(error.row), error.error))
+                .collect();
+
+            // 테이블 단위의 오류는 행 단위의 오류와 같이, 바뀐 경우에만 기록합니다.
+            let mut last_errors = self.last_errors.lock().await;
+            match result {
+                Ok(()) => {
+                    last_errors.remove(table.name());
+                }
+                Err(error) => {
+                    error!("failed to manage {}: {:#}", table.name(), &error);
+                    let error = format!("{:#}", error);
+                    if last_errors.get(table.name()) != Some(&error) {
+                        last_errors.insert(table.name().to_string(), error.clone());
+                        errors.push((None, error));
+                    }
+                }
+            }
+            drop(last_errors);
+
+            if !errors.is_empty() {
+                self.log_errors(table.as_ref(), errors, ctx.now()).await;
+            }
+        }
         Ok(())
     }
 }
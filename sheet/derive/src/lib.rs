@@ -0,0 +1,381 @@
+use inflector::Inflector;
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{
+    parse_macro_input, spanned::Spanned, Attribute, Data, DeriveInput, Error, Fields, Lit, Meta,
+    NestedMeta, Result,
+};
+
+/// 하나의 구조체로 관리할 테이블을 정의합니다.
+///
+/// 구조체에는 `JsonSchema`도 함께 구현되어 있어야 합니다.
+///
+/// ## 구조체 속성
+/// * `range`: 필드 행 범위 (필수)
+/// * `name`: 테이블 이름 (기본값: 구조체 이름의 snake_case)
+/// * `key`: 행을 구분하는 필드
+///
+/// ## 필드 속성
+/// * `label`: 필드 행에 표시되는 이름
+/// * `read_only`: 사람이 수정할 수 없는 열
+/// * `choices`: 필드 타입(열거형)의 값들 중 하나를 선택하는 열
+///
+/// 필드 이름은 `#[serde(rename = "...")]`와 `#[serde(rename_all = "...")]`로 바뀐 이름을 사용합니다.
+///
+/// ```ignore
+/// #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, SheetTable)]
+/// #[sheet(range = "Purchase!A1:Z1", key = "id")]
+/// struct Purchase {
+///     #[sheet(read_only)]
+///     id: String,
+///     #[sheet(label = "구매 상태", choices)]
+///     status: Option<PurchaseStatus>,
+/// }
+/// ```
+#[proc_macro_derive(SheetTable, attributes(sheet))]
+pub fn derive_sheet_table(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(|error| error.to_compile_error())
+        .into()
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new(
+                    input.ident.span(),
+                    "SheetTable requires a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new(
+                input.ident.span(),
+                "SheetTable is not supported for enums and unions",
+            ))
+        }
+    };
+
+    let mut name = input.ident.to_string().to_snake_case();
+    let mut range = None;
+    let mut key = None;
+    for meta in parse_attrs(&input.attrs)? {
+        match &meta {
+            NestedMeta::Meta(Meta::NameValue(e)) if e.path.is_ident("name") => {
+                name = parse_str(&e.lit)?;
+            }
+            NestedMeta::Meta(Meta::NameValue(e)) if e.path.is_ident("range") => {
+                range = Some(parse_str(&e.lit)?);
+            }
+            NestedMeta::Meta(Meta::NameValue(e)) if e.path.is_ident("key") => {
+                key = Some((parse_str(&e.lit)?, e.lit.span()));
+            }
+            _ => return Err(Error::new(meta.span(), "unknown sheet attribute")),
+        }
+    }
+    let range = range.ok_or_else(|| {
+        Error::new(
+            input.ident.span(),
+            "missing the fields range: #[sheet(range = \"...\")]",
+        )
+    })?;
+
+    let mut rename_all = None;
+    for meta in parse_serde_attrs(&input.attrs)? {
+        if let Some((rule, span)) = parse_serde_name(&meta, "rename_all")? {
+            rename_all = Some(
+                RenameRule::parse(&rule)
+                    .ok_or_else(|| Error::new(span, format!("unknown rename rule: {}", rule)))?,
+            );
+        }
+    }
+
+    let mut columns = vec![];
+    let mut field_names = vec![];
+    for field in fields {
+        let mut field_name = field.ident.as_ref().unwrap().to_string();
+        if let Some(rule) = rename_all {
+            field_name = rule.apply(&field_name);
+        }
+        for meta in parse_serde_attrs(&field.attrs)? {
+            if let Some((rename, _)) = parse_serde_name(&meta, "rename")? {
+                field_name = rename;
+            }
+        }
+
+        let mut label = None;
+        let mut read_only = false;
+        let mut choices = false;
+        for meta in parse_attrs(&field.attrs)? {
+            match &meta {
+                NestedMeta::Meta(Meta::NameValue(e)) if e.path.is_ident("label") => {
+                    label = Some(parse_str(&e.lit)?);
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("read_only") => {
+                    read_only = true;
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("choices") => {
+                    choices = true;
+                }
+                _ => return Err(Error::new(meta.span(), "unknown sheet attribute")),
+            }
+        }
+
+        if label.is_some() || read_only || choices {
+            let ty = &field.ty;
+            let label = match label {
+                Some(label) => quote! { ::std::option::Option::Some(#label.to_string()) },
+                None => quote! { ::std::option::Option::None },
+            };
+            let choices = if choices {
+                quote! { ::smartx_whitedog_sheet_client::enum_values::<#ty>() }
+            } else {
+                quote! { ::std::vec::Vec::new() }
+            };
+            columns.push(quote! {
+                ::smartx_whitedog_sheet_client::Column {
+                    field: #field_name.to_string(),
+                    label: #label,
+                    read_only: #read_only,
+                    choices: #choices,
+                }
+            });
+        }
+        field_names.push(field_name);
+    }
+
+    let key = match key {
+        Some((key, _)) if field_names.contains(&key) => {
+            quote! { ::std::option::Option::Some(#key) }
+        }
+        Some((key, span)) => return Err(Error::new(span, format!("no such field: {}", key))),
+        None => quote! { ::std::option::Option::None },
+    };
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::smartx_whitedog_sheet_client::SheetTable for #ident #ty_generics
+        #where_clause
+        {
+            const NAME: &'static str = #name;
+            const RANGE: &'static str = #range;
+            const KEY: ::std::option::Option<&'static str> = #key;
+
+            fn columns() -> ::std::vec::Vec<::smartx_whitedog_sheet_client::Column> {
+                ::std::vec![#(#columns),*]
+            }
+        }
+    })
+}
+
+/// `#[sheet(...)]` 속성들의 항목을 불러옵니다.
+fn parse_attrs(attrs: &[Attribute]) -> Result<Vec<NestedMeta>> {
+    parse_attrs_of("sheet", attrs)
+}
+
+/// `#[serde(...)]` 속성들의 항목을 불러옵니다.
+///
+/// 필드 이름이 바뀐 경우, 바뀐 이름을 사용하기 위함입니다.
+fn parse_serde_attrs(attrs: &[Attribute]) -> Result<Vec<NestedMeta>> {
+    parse_attrs_of("serde", attrs)
+}
+
+/// `rename = "..."`와 같은 serde 속성의 값을 불러옵니다.
+///
+/// `rename(serialize = "...", deserialize = "...")`와 같이 나누어 지정했다면,
+/// `JsonSchema`와 같이 `deserialize`의 값을 사용합니다.
+fn parse_serde_name(meta: &NestedMeta, name: &str) -> Result<Option<(String, Span)>> {
+    match meta {
+        NestedMeta::Meta(Meta::NameValue(e)) if e.path.is_ident(name) => {
+            Ok(Some((parse_str(&e.lit)?, e.lit.span())))
+        }
+        NestedMeta::Meta(Meta::List(list)) if list.path.is_ident(name) => {
+            for meta in &list.nested {
+                if let NestedMeta::Meta(Meta::NameValue(e)) = meta {
+                    if e.path.is_ident("deserialize") {
+                        return Ok(Some((parse_str(&e.lit)?, e.lit.span())));
+                    }
+                }
+            }
+            Ok(None)
+        }
+        _ => Ok(None),
+    }
+}
+
+fn parse_attrs_of(name: &str, attrs: &[Attribute]) -> Result<Vec<NestedMeta>> {
+    let mut metas = vec![];
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident(name)) {
+        match attr.parse_meta()? {
+            Meta::List(list) => metas.extend(list.nested),
+            meta => {
+                return Err(Error::new(
+                    meta.span(),
+                    format!("expected #[{}(...)]", name),
+                ))
+            }
+        }
+    }
+    Ok(metas)
+}
+
+fn parse_str(lit: &Lit) -> Result<String> {
+    match lit {
+        Lit::Str(lit) => Ok(lit.value()),
+        _ => Err(Error::new(lit.span(), "expected a string literal")),
+    }
+}
+
+/// 구조체의 `#[serde(rename_all = "...")]`로 바뀌는 필드 이름의 규칙입니다.
+///
+/// serde와 같은 방법으로 필드 이름을 바꿉니다.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    fn parse(rule: &str) -> Option<Self> {
+        match rule {
+            "lowercase" => Some(Self::Lower),
+            "UPPERCASE" => Some(Self::Upper),
+            "PascalCase" => Some(Self::Pascal),
+            "camelCase" => Some(Self::Camel),
+            "snake_case" => Some(Self::Snake),
+            "SCREAMING_SNAKE_CASE" => Some(Self::ScreamingSnake),
+            "kebab-case" => Some(Self::Kebab),
+            "SCREAMING-KEBAB-CASE" => Some(Self::ScreamingKebab),
+            _ => None,
+        }
+    }
+
+    /// snake_case인 필드 이름에 규칙을 적용합니다.
+    fn apply(self, field: &str) -> String {
+        match self {
+            Self::Lower | Self::Snake => field.to_string(),
+            Self::Upper | Self::ScreamingSnake => field.to_ascii_uppercase(),
+            Self::Pascal => {
+                let mut pascal = String::new();
+                let mut capitalize = true;
+                for ch in field.chars() {
+                    if ch == '_' {
+                        capitalize = true;
+                    } else if capitalize {
+                        pascal.push(ch.to_ascii_uppercase());
+                        capitalize = false;
+                    } else {
+                        pascal.push(ch);
+                    }
+                }
+                pascal
+            }
+            Self::Camel => {
+                let pascal = Self::Pascal.apply(field);
+                let mut chars = pascal.chars();
+                match chars.next() {
+                    Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+                    None => pascal,
+                }
+            }
+            Self::Kebab => field.replace('_', "-"),
+            Self::ScreamingKebab => field.to_ascii_uppercase().replace('_', "-"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn test_rename_rule() {
+        let cases = [
+            ("lowercase", "purchase_id"),
+            ("UPPERCASE", "PURCHASE_ID"),
+            ("PascalCase", "PurchaseId"),
+            ("camelCase", "purchaseId"),
+            ("snake_case", "purchase_id"),
+            ("SCREAMING_SNAKE_CASE", "PURCHASE_ID"),
+            ("kebab-case", "purchase-id"),
+            ("SCREAMING-KEBAB-CASE", "PURCHASE-ID"),
+        ];
+        for (rule, expected) in &cases {
+            let rule = RenameRule::parse(rule).unwrap();
+            assert_eq!(rule.apply("purchase_id"), *expected);
+        }
+        assert_eq!(RenameRule::parse("Title Case"), None);
+    }
+
+    #[test]
+    fn test_expand_rename_all() {
+        let input: DeriveInput = parse_quote! {
+            #[serde(rename_all = "camelCase")]
+            #[sheet(range = "Purchase!A1:Z1", key = "purchaseId")]
+            struct Purchase {
+                purchase_id: String,
+                #[sheet(read_only)]
+                item_name: String,
+                #[serde(rename = "state")]
+                #[sheet(label = "구매 상태")]
+                status: String,
+            }
+        };
+        let output = expand(input).unwrap().to_string();
+        assert!(output.contains("\"purchaseId\""));
+        assert!(output.contains("\"itemName\""));
+        assert!(output.contains("\"state\""));
+        assert!(!output.contains("\"item_name\""));
+    }
+
+    #[test]
+    fn test_expand_rename_all_deserialize() {
+        let input: DeriveInput = parse_quote! {
+            #[serde(rename_all(serialize = "UPPERCASE", deserialize = "kebab-case"))]
+            #[sheet(range = "Purchase!A1:Z1", key = "purchase-id")]
+            struct Purchase {
+                purchase_id: String,
+            }
+        };
+        assert!(expand(input).is_ok());
+    }
+
+    #[test]
+    fn test_expand_unknown_rename_rule() {
+        let input: DeriveInput = parse_quote! {
+            #[serde(rename_all = "Title Case")]
+            #[sheet(range = "Purchase!A1:Z1")]
+            struct Purchase {
+                purchase_id: String,
+            }
+        };
+        let error = expand(input).unwrap_err();
+        assert_eq!(error.to_string(), "unknown rename rule: Title Case");
+    }
+
+    #[test]
+    fn test_expand_key_uses_serialized_name() {
+        let input: DeriveInput = parse_quote! {
+            #[serde(rename_all = "camelCase")]
+            #[sheet(range = "Purchase!A1:Z1", key = "purchase_id")]
+            struct Purchase {
+                purchase_id: String,
+            }
+        };
+        let error = expand(input).unwrap_err();
+        assert_eq!(error.to_string(), "no such field: purchase_id");
+    }
+}